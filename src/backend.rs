@@ -0,0 +1,396 @@
+//! Backends running monerod and monero-wallet-rpc for a `Monero`.
+//!
+//! `Docker` runs both in a container, `Local` spawns the binaries directly for
+//! machines that cannot run Docker.
+
+use anyhow::{anyhow, bail, Result};
+use rand::Rng;
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Debug,
+    fs::{self, File},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process::{self, Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use testcontainers::{clients::Cli, core::Port, Container, Docker as _};
+
+use crate::{
+    image::{self, MonerodArgs, WalletArgs, MONEROD_RPC_PORT, WALLET_RPC_PORT},
+    rpc::{Certificate, Credentials},
+};
+
+/// How long `Local` waits for monerod and monero-wallet-rpc to accept RPC
+/// connections.
+const LOCAL_STARTUP_TIMEOUT_SECS: u64 = 60;
+
+/// Poll interval while waiting for a local process to accept connections.
+const LOCAL_STARTUP_POLL_MILLIS: u64 = 100;
+
+/// A running monerod with a monero-wallet-rpc for the miner wallet and one
+/// for each additional wallet. Stops everything when dropped.
+pub trait Backend: Debug {
+    /// Local port of the monerod RPC.
+    fn monerod_rpc_port(&self) -> u16;
+
+    /// Local port of the monero-wallet-rpc holding the miner wallet.
+    fn wallet_rpc_port(&self) -> u16;
+
+    /// Local ports of the additional monero-wallet-rpc instances, by wallet
+    /// name.
+    fn wallet_rpc_ports(&self) -> HashMap<String, u16>;
+
+    /// Credentials every monero-wallet-rpc requires, if started with login.
+    fn wallet_rpc_login(&self) -> Option<Credentials>;
+
+    /// Certificate of every RPC server, if started with TLS.
+    fn rpc_certificate(&self) -> Option<Certificate>;
+
+    /// The container running everything, if run in Docker.
+    fn container(&self) -> Option<&Container<'_, Cli, image::Monero>> {
+        None
+    }
+}
+
+/// Regtest monerod and monero-wallet-rpc in a single docker container.
+#[derive(Debug)]
+pub struct Docker<'c> {
+    pub container: Container<'c, Cli, image::Monero>,
+    monerod_rpc_port: u16,
+    wallet_rpc_port: u16,
+    wallet_rpc_ports: HashMap<String, u16>,
+    wallet_rpc_login: Option<Credentials>,
+    rpc_certificate: Option<Certificate>,
+    /// Host directory mounted into the container holding the certificate.
+    certificate_dir: Option<PathBuf>,
+}
+
+impl<'c> Docker<'c> {
+    /// Starts a container with an additional monero-wallet-rpc for each name
+    /// in `wallets`, all ports are mapped to random local ports. monerod runs
+    /// with `monerod` and every wallet-rpc with `wallet`, except for the RPC
+    /// bind addresses and ports the port mapping needs and the wallet-rpcs'
+    /// daemon address. With `wallet_rpc_login` every wallet-rpc requires
+    /// login, with `rpc_certificate` every RPC server uses TLS. Fails if
+    /// `wallet` is inconsistent.
+    pub fn start(
+        cli: &'c Cli,
+        wallets: &[&str],
+        monerod: MonerodArgs,
+        wallet: WalletArgs,
+        wallet_rpc_login: Option<Credentials>,
+        rpc_certificate: Option<Certificate>,
+    ) -> Result<Self> {
+        let mut rng = rand::thread_rng();
+        let monerod_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
+        let wallet_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
+
+        let monerod = MonerodArgs {
+            rpc_bind_ip: "0.0.0.0".to_owned(),
+            rpc_bind_port: MONEROD_RPC_PORT,
+            ..monerod
+        };
+        let wallet = WalletArgs {
+            rpc_bind_ip: "0.0.0.0".to_owned(),
+            daemon_address: format!("localhost:{}", MONEROD_RPC_PORT),
+            ..wallet
+        };
+        let mut image = image::Monero::default()
+            .with_monerod_args(monerod)
+            .with_default_wallet_args(WalletArgs {
+                rpc_bind_port: WALLET_RPC_PORT,
+                ..wallet.clone()
+            })?
+            .with_mapped_port(Port {
+                local: monerod_rpc_port,
+                internal: MONEROD_RPC_PORT,
+            })
+            .with_mapped_port(Port {
+                local: wallet_rpc_port,
+                internal: WALLET_RPC_PORT,
+            });
+
+        let mut wallet_rpc_ports = HashMap::new();
+        for (name, offset) in wallets.iter().zip(1..) {
+            let local: u16 = rng.gen_range(1024, u16::MAX);
+            let internal = WALLET_RPC_PORT + offset;
+
+            image = image
+                .with_wallet_args(WalletArgs {
+                    rpc_bind_port: internal,
+                    ..wallet.clone()
+                })?
+                .with_mapped_port(Port { local, internal });
+            wallet_rpc_ports.insert((*name).to_owned(), local);
+        }
+
+        if let Some(login) = &wallet_rpc_login {
+            image = image.with_wallet_rpc_login(login);
+        }
+
+        let certificate_dir = match &rpc_certificate {
+            Some(certificate) => {
+                let dir = temp_dir("monero-harness-tls")?;
+                certificate.write_to(&dir)?;
+                Some(dir)
+            }
+            None => None,
+        };
+        if let Some(dir) = &certificate_dir {
+            image = image.with_rpc_ssl(&path_arg(dir)?);
+        }
+
+        println!("running image ...");
+        let container = cli.run(image);
+        println!("image ran");
+
+        Ok(Self {
+            container,
+            monerod_rpc_port,
+            wallet_rpc_port,
+            wallet_rpc_ports,
+            wallet_rpc_login,
+            rpc_certificate,
+            certificate_dir,
+        })
+    }
+}
+
+impl Backend for Docker<'_> {
+    fn monerod_rpc_port(&self) -> u16 {
+        self.monerod_rpc_port
+    }
+
+    fn wallet_rpc_port(&self) -> u16 {
+        self.wallet_rpc_port
+    }
+
+    fn wallet_rpc_ports(&self) -> HashMap<String, u16> {
+        self.wallet_rpc_ports.clone()
+    }
+
+    fn wallet_rpc_login(&self) -> Option<Credentials> {
+        self.wallet_rpc_login.clone()
+    }
+
+    fn rpc_certificate(&self) -> Option<Certificate> {
+        self.rpc_certificate.clone()
+    }
+
+    fn container(&self) -> Option<&Container<'_, Cli, image::Monero>> {
+        Some(&self.container)
+    }
+}
+
+impl Drop for Docker<'_> {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.certificate_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Regtest monerod and monero-wallet-rpc spawned from local binaries, with a
+/// temporary data directory that is removed on drop. Logs of each process are
+/// written to the data directory.
+#[derive(Debug)]
+pub struct Local {
+    /// Data directory of monerod and wallet directory of all wallet-rpcs.
+    pub data_dir: PathBuf,
+    children: Vec<Child>,
+    monerod_rpc_port: u16,
+    wallet_rpc_port: u16,
+    wallet_rpc_ports: HashMap<String, u16>,
+    wallet_rpc_login: Option<Credentials>,
+    rpc_certificate: Option<Certificate>,
+}
+
+impl Local {
+    /// Spawns `monerod` and `monero-wallet-rpc` found in `bin_dir`, with an
+    /// additional monero-wallet-rpc for each name in `wallets`, and waits
+    /// until all of them accept RPC connections. monerod runs with `monerod`
+    /// except for its ports, RPC bind address and data directory, every
+    /// wallet-rpc with `wallet` except for its port, RPC bind address, wallet
+    /// directory and daemon address. With `wallet_rpc_login` every wallet-rpc
+    /// requires login, with `rpc_certificate` every RPC server uses TLS.
+    pub fn start(
+        bin_dir: impl AsRef<Path>,
+        wallets: &[&str],
+        monerod: MonerodArgs,
+        wallet: WalletArgs,
+        wallet_rpc_login: Option<Credentials>,
+        rpc_certificate: Option<Certificate>,
+    ) -> Result<Self> {
+        let bin_dir = bin_dir.as_ref();
+        let data_dir = temp_dir("monero-harness")?;
+
+        // Set up before spawning anything so a failure below cleans up the
+        // data dir and kills whatever was already spawned.
+        let mut local = Self {
+            data_dir,
+            children: vec![],
+            monerod_rpc_port: free_port()?,
+            wallet_rpc_port: free_port()?,
+            wallet_rpc_ports: HashMap::new(),
+            wallet_rpc_login,
+            rpc_certificate,
+        };
+
+        let ssl_dir = path_arg(&local.data_dir)?;
+        if let Some(certificate) = &local.rpc_certificate {
+            certificate.write_to(&local.data_dir)?;
+        }
+
+        let mut monerod = MonerodArgs {
+            rpc_bind_ip: "127.0.0.1".to_owned(),
+            rpc_bind_port: local.monerod_rpc_port,
+            // Regtest nodes share the default p2p and ZMQ RPC ports, which
+            // would keep harnesses from running side by side.
+            p2p_bind_port: free_port()?,
+            zmq_rpc_bind_port: free_port()?,
+            data_dir: path_arg(&local.data_dir)?,
+            ..monerod
+        };
+        if local.rpc_certificate.is_some() {
+            monerod = monerod.with_rpc_ssl(&ssl_dir);
+        }
+        local.spawn(bin_dir, "monerod", &monerod.args())?;
+        wait_for_port(&mut local.children[0], local.monerod_rpc_port)?;
+
+        let daemon_address = format!("127.0.0.1:{}", local.monerod_rpc_port);
+        let mut wallet_ports = vec![(String::new(), local.wallet_rpc_port)];
+        for name in wallets {
+            wallet_ports.push(((*name).to_owned(), free_port()?));
+        }
+
+        for (name, port) in wallet_ports {
+            let mut args = WalletArgs {
+                wallet_dir: path_arg(&local.data_dir)?,
+                rpc_bind_ip: "127.0.0.1".to_owned(),
+                rpc_bind_port: port,
+                daemon_address: daemon_address.clone(),
+                ..wallet.clone()
+            };
+            if let Some(login) = &local.wallet_rpc_login {
+                args = WalletArgs {
+                    disable_rpc_login: false,
+                    rpc_login: login.login(),
+                    ..args
+                };
+            }
+            if local.rpc_certificate.is_some() {
+                args = args.with_rpc_ssl(&ssl_dir);
+            }
+            args.validate()?;
+            let log = format!("monero-wallet-rpc-{}", port);
+            local.spawn(bin_dir, &log, &args.args())?;
+
+            let child = local.children.last_mut().expect("just spawned");
+            wait_for_port(child, port)?;
+
+            if !name.is_empty() {
+                local.wallet_rpc_ports.insert(name, port);
+            }
+        }
+
+        Ok(local)
+    }
+
+    /// Spawns the binary named by the first element of `argv` from `bin_dir`,
+    /// logging to `<log>.log`.
+    fn spawn(&mut self, bin_dir: &Path, log: &str, argv: &[String]) -> Result<()> {
+        let binary = bin_dir.join(&argv[0]);
+        let log = File::create(self.data_dir.join(format!("{}.log", log)))?;
+
+        let child = Command::new(&binary)
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .map_err(|e| anyhow!("failed to spawn {}: {}", binary.display(), e))?;
+        self.children.push(child);
+
+        Ok(())
+    }
+}
+
+impl Backend for Local {
+    fn monerod_rpc_port(&self) -> u16 {
+        self.monerod_rpc_port
+    }
+
+    fn wallet_rpc_port(&self) -> u16 {
+        self.wallet_rpc_port
+    }
+
+    fn wallet_rpc_ports(&self) -> HashMap<String, u16> {
+        self.wallet_rpc_ports.clone()
+    }
+
+    fn wallet_rpc_login(&self) -> Option<Credentials> {
+        self.wallet_rpc_login.clone()
+    }
+
+    fn rpc_certificate(&self) -> Option<Certificate> {
+        self.rpc_certificate.clone()
+    }
+}
+
+impl Drop for Local {
+    fn drop(&mut self) {
+        // Wallet-rpcs first, they talk to monerod.
+        for child in self.children.iter_mut().rev() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        let _ = fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+/// Creates a new directory in the system temp dir named `prefix`, the process
+/// id and a random number.
+fn temp_dir(prefix: &str) -> Result<PathBuf> {
+    let dir = env::temp_dir().join(format!(
+        "{}-{}-{}",
+        prefix,
+        process::id(),
+        rand::thread_rng().gen::<u32>()
+    ));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Asks the OS for a free local port. Another process could grab it before we
+/// bind it, which is unlikely enough for tests.
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// `MonerodArgs` and `WalletArgs` hold paths as strings.
+fn path_arg(path: &Path) -> Result<String> {
+    path.to_str()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| anyhow!("path is not valid UTF-8: {}", path.display()))
+}
+
+/// Waits until `port` accepts connections, failing if `child` exits first.
+fn wait_for_port(child: &mut Child, port: u16) -> Result<()> {
+    let start = Instant::now();
+
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        if let Some(status) = child.try_wait()? {
+            bail!("process listening on port {} exited with {}", port, status);
+        }
+        if start.elapsed() > Duration::from_secs(LOCAL_STARTUP_TIMEOUT_SECS) {
+            bail!("timed out waiting for port {}", port);
+        }
+        sleep(Duration::from_millis(LOCAL_STARTUP_POLL_MILLIS));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! Record-and-replay of RPC traffic.
+//!
+//! A recording `Cassette` set as `ClientConfig::cassette` writes every
+//! request/response pair the RPC clients exchange with a live monerod or
+//! monero-wallet-rpc to a cassette file. A replaying `Cassette` answers the
+//! clients' requests with the recorded responses from the file without
+//! touching the network, so a test written against the Docker harness can be
+//! re-run offline. Pass the config to `Monero::with_client_config()` to
+//! record or replay the harness's own calls too.
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// A recorded request/response pair.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Interaction {
+    /// Request path, e.g. `/json_rpc`.
+    pub path: String,
+    /// Request body, the JSON RPC `id` is removed as it does not take part in
+    /// matching.
+    pub request: Value,
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Response body.
+    pub response: String,
+}
+
+impl Interaction {
+    fn matches(&self, path: &str, request: &Value) -> bool {
+        self.path == path && &self.request == request
+    }
+}
+
+/// A cassette file being recorded or replayed, clones share the file.
+#[derive(Clone, Debug)]
+pub struct Cassette {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    mode: Mode,
+    path: PathBuf,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+#[derive(Debug)]
+enum Mode {
+    Record,
+    /// Holds whether each interaction has been played.
+    Replay(Mutex<Vec<bool>>),
+}
+
+impl Cassette {
+    /// Records into the cassette file at `path`. The file is overwritten and
+    /// updated after every request, so a failing test still leaves a
+    /// cassette behind.
+    pub fn record(path: impl AsRef<Path>) -> Result<Self> {
+        let cassette = Self::new(Mode::Record, path.as_ref().to_owned(), vec![]);
+        cassette.save()?;
+
+        Ok(cassette)
+    }
+
+    /// Replays the cassette file at `path`. A request is answered with the
+    /// first unplayed interaction with the same path and body, so repeated
+    /// requests get their responses in recorded order. Requests without a
+    /// match fail.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| anyhow!("failed to read cassette {}: {}", path.display(), e))?;
+        let interactions: Vec<Interaction> = serde_json::from_str(&contents)?;
+        let played = vec![false; interactions.len()];
+
+        Ok(Self::new(
+            Mode::Replay(Mutex::new(played)),
+            path,
+            interactions,
+        ))
+    }
+
+    fn new(mode: Mode, path: PathBuf, interactions: Vec<Interaction>) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                mode,
+                path,
+                interactions: Mutex::new(interactions),
+            }),
+        }
+    }
+
+    /// Interactions recorded so far, or all interactions of a replayed
+    /// cassette.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.inner
+            .interactions
+            .lock()
+            .expect("poisoned lock")
+            .clone()
+    }
+
+    /// Interactions of a replayed cassette that have not been requested, empty
+    /// when recording.
+    pub fn unplayed(&self) -> Vec<Interaction> {
+        match &self.inner.mode {
+            Mode::Record => vec![],
+            Mode::Replay(played) => {
+                // Same lock order as `replayed`.
+                let interactions = self.inner.interactions.lock().expect("poisoned lock");
+                let played = played.lock().expect("poisoned lock");
+                interactions
+                    .iter()
+                    .zip(played.iter())
+                    .filter(|(_, played)| !**played)
+                    .map(|(interaction, _)| interaction.clone())
+                    .collect()
+            }
+        }
+    }
+
+    /// The recorded response to a request to `path` with `body` when
+    /// replaying, none when recording.
+    pub(crate) fn replayed(&self, path: &str, body: &[u8]) -> Result<Option<(StatusCode, String)>> {
+        let played = match &self.inner.mode {
+            Mode::Record => return Ok(None),
+            Mode::Replay(played) => played,
+        };
+
+        let request = normalize(body);
+        let interactions = self.inner.interactions.lock().expect("poisoned lock");
+        let mut played = played.lock().expect("poisoned lock");
+
+        let found = interactions
+            .iter()
+            .zip(played.iter_mut())
+            .find(|(interaction, played)| !**played && interaction.matches(path, &request));
+
+        match found {
+            Some((interaction, played)) => {
+                *played = true;
+                let status = StatusCode::from_u16(interaction.status)?;
+                Ok(Some((status, interaction.response.clone())))
+            }
+            None => bail!(
+                "no unplayed interaction in cassette for {} {}",
+                path,
+                request
+            ),
+        }
+    }
+
+    /// Records a request to `path` with `body` and its response when
+    /// recording.
+    pub(crate) fn record_interaction(
+        &self,
+        path: &str,
+        body: &[u8],
+        status: StatusCode,
+        response: &str,
+    ) -> Result<()> {
+        if let Mode::Replay(_) = self.inner.mode {
+            return Ok(());
+        }
+
+        self.inner
+            .interactions
+            .lock()
+            .expect("poisoned lock")
+            .push(Interaction {
+                path: path.to_owned(),
+                request: normalize(body),
+                status: status.as_u16(),
+                response: response.to_owned(),
+            });
+
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let interactions = self.inner.interactions.lock().expect("poisoned lock");
+        let path = &self.inner.path;
+        // Pretty printed so cassettes diff well when re-recorded.
+        let contents = serde_json::to_string_pretty(&*interactions)?;
+        fs::write(path, contents)
+            .map_err(|e| anyhow!("failed to write cassette {}: {}", path.display(), e))
+    }
+}
+
+/// Parses a request body for matching, dropping the JSON RPC `id`. Bodies
+/// that are not JSON are kept as a string.
+fn normalize(body: &[u8]) -> Value {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Object(mut object)) => {
+            object.remove("id");
+            Value::Object(object)
+        }
+        Ok(value) => value,
+        Err(_) => Value::String(String::from_utf8_lossy(body).into_owned()),
+    }
+}
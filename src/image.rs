@@ -1,17 +1,28 @@
-use std::{collections::HashMap, env::var, thread::sleep, time::Duration};
+use anyhow::{bail, Result};
+use std::{collections::HashMap, env::var, path::Path, thread::sleep, time::Duration};
 use testcontainers::{
     core::{Container, Docker, Port, WaitForMessage},
     Image,
 };
 
+use crate::rpc::{
+    tls::{CERTIFICATE_FILE, PRIVATE_KEY_FILE},
+    Credentials,
+};
+
 pub const MONEROD_RPC_PORT: u16 = 48081;
+pub const MONEROD_P2P_PORT: u16 = 48080;
 pub const WALLET_RPC_PORT: u16 = 48083;
 
+/// Where `with_rpc_ssl()` mounts the certificate directory in the container.
+const RPC_SSL_DIR: &str = "/rpc-ssl";
+
 #[derive(Debug)]
 pub struct Monero {
     tag: String,
     args: Args,
     ports: Option<Vec<Port>>,
+    volumes: HashMap<String, String>,
     entrypoint: Option<String>,
 }
 
@@ -26,13 +37,13 @@ impl Image for Monero {
     }
 
     fn wait_until_ready<D: Docker>(&self, container: &Container<'_, D, Self>) {
-        container
-            .logs()
-            .stdout
-            .wait_for_message(
-                "The daemon is running offline and will not attempt to sync to the Monero network",
-            )
-            .unwrap();
+        let message = if self.args.monerod.offline {
+            "The daemon is running offline and will not attempt to sync to the Monero network"
+        } else {
+            "The daemon will start synchronizing with the network"
+        };
+
+        container.logs().stdout.wait_for_message(message).unwrap();
 
         let additional_sleep_period =
             var("MONERO_ADDITIONAL_SLEEP_PERIOD").map(|value| value.parse());
@@ -49,7 +60,7 @@ impl Image for Monero {
     }
 
     fn volumes(&self) -> Self::Volumes {
-        HashMap::new()
+        self.volumes.clone()
     }
 
     fn env_vars(&self) -> Self::EnvVars {
@@ -82,6 +93,7 @@ impl Default for Monero {
             tag: "v0.16.0.3".into(),
             args: Args::default(),
             ports: None,
+            volumes: HashMap::new(),
             entrypoint: Some("".into()),
         }
     }
@@ -95,6 +107,65 @@ impl Monero {
         }
     }
 
+    /// Run monerod with `args` instead of the regtest defaults.
+    pub fn with_monerod_args(mut self, args: MonerodArgs) -> Self {
+        self.args.monerod = args;
+        self
+    }
+
+    /// Run monerod online, peering only with `exclusive_nodes` (host:port).
+    pub fn with_exclusive_nodes(mut self, exclusive_nodes: Vec<String>) -> Self {
+        self.args.monerod.offline = false;
+        // Peers on a docker network have private IPs.
+        self.args.monerod.allow_local_ip = true;
+        self.args.monerod.p2p_bind_port = MONEROD_P2P_PORT;
+        self.args.monerod.add_exclusive_node = exclusive_nodes;
+        self
+    }
+
+    /// Run an additional monero-wallet-rpc listening on `rpc_bind_port`.
+    pub fn with_wallet(mut self, rpc_bind_port: u16) -> Self {
+        self.args.wallets.push(WalletArgs {
+            rpc_bind_port,
+            ..WalletArgs::default()
+        });
+        self
+    }
+
+    /// Run the default monero-wallet-rpc with `args` instead of the defaults,
+    /// fails if `args` are inconsistent.
+    pub fn with_default_wallet_args(mut self, args: WalletArgs) -> Result<Self> {
+        args.validate()?;
+        self.args.wallets[0] = args;
+        Ok(self)
+    }
+
+    /// Run an additional monero-wallet-rpc configured by `args`, fails if
+    /// `args` are inconsistent.
+    pub fn with_wallet_args(mut self, args: WalletArgs) -> Result<Self> {
+        args.validate()?;
+        self.args.wallets.push(args);
+        Ok(self)
+    }
+
+    /// Require HTTP digest authentication with `credentials` on every
+    /// monero-wallet-rpc instead of disabling RPC login.
+    pub fn with_wallet_rpc_login(mut self, credentials: &Credentials) -> Self {
+        self.args.wallet_rpc_login = credentials.login();
+        self
+    }
+
+    /// Enable TLS on the RPC of monerod and every monero-wallet-rpc with the
+    /// certificate and key written by `Certificate::write_to()` to
+    /// `certificate_dir` on the host, which is mounted into the container.
+    /// The wallet-rpcs trust monerod's certificate.
+    pub fn with_rpc_ssl(mut self, certificate_dir: &str) -> Self {
+        self.volumes
+            .insert(certificate_dir.to_owned(), RPC_SSL_DIR.to_owned());
+        self.args.rpc_ssl_dir = RPC_SSL_DIR.to_owned();
+        self
+    }
+
     pub fn with_mapped_port<P: Into<Port>>(mut self, port: P) -> Self {
         let mut ports = self.ports.unwrap_or_default();
         ports.push(port.into());
@@ -103,10 +174,27 @@ impl Monero {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct Args {
     monerod: MonerodArgs,
-    wallet: WalletArgs,
+    wallets: Vec<WalletArgs>,
+    /// `--rpc-login` of every wallet-rpc if not empty.
+    wallet_rpc_login: String,
+    /// Directory holding the RPC certificate and key, TLS on every RPC if
+    /// not empty.
+    rpc_ssl_dir: String,
+}
+
+/// A single monerod with a single monero-wallet-rpc, the default one.
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            monerod: MonerodArgs::default(),
+            wallets: vec![WalletArgs::default()],
+            wallet_rpc_login: String::new(),
+            rpc_ssl_dir: String::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -122,6 +210,61 @@ pub struct MonerodArgs {
     pub rpc_bind_port: u16,
     pub fixed_difficulty: u32,
     pub data_dir: String,
+    pub p2p_bind_port: u16,
+    pub allow_local_ip: bool,
+    pub add_exclusive_node: Vec<String>,
+    /// Network to run on, regtest runs on top of it.
+    pub network: NetworkType,
+    pub p2p_bind_ip: String,
+    pub add_peer: Vec<String>,
+    pub zmq_rpc_bind_ip: String,
+    pub zmq_rpc_bind_port: u16,
+    /// ZMQ pub endpoint, e.g. `tcp://0.0.0.0:18083`.
+    pub zmq_pub: String,
+    pub no_zmq: bool,
+    pub log_level: u32,
+    /// Log categories, e.g. `net.p2p:DEBUG`, used instead of `log_level` if
+    /// not empty.
+    pub log_categories: Vec<String>,
+    /// Command run for each new block, `%s` is replaced by the block hash.
+    pub block_notify: String,
+    pub db_sync_mode: Option<DbSyncMode>,
+    pub max_txpool_weight: u64,
+    pub prune_blockchain: bool,
+    pub restricted_rpc: bool,
+    /// RPC credentials as `username:password`.
+    pub rpc_login: String,
+    pub rpc_ssl: Option<SslMode>,
+    pub rpc_ssl_private_key: String,
+    pub rpc_ssl_certificate: String,
+    /// Raw flags appended as is, for options without a typed field.
+    pub extra_args: Vec<String>,
+}
+
+/// Network monerod runs on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkType {
+    Mainnet,
+    Testnet,
+    Stagenet,
+}
+
+/// Blockchain database sync mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbSyncMode {
+    Safe,
+    Fast,
+    Fastest,
+}
+
+impl DbSyncMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DbSyncMode::Safe => "safe",
+            DbSyncMode::Fast => "fast",
+            DbSyncMode::Fastest => "fastest",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -133,6 +276,52 @@ pub struct WalletArgs {
     pub rpc_bind_port: u16,
     pub daemon_address: String,
     pub log_level: u32,
+    /// RPC credentials as `username:password`, requires
+    /// `disable_rpc_login` to be false.
+    pub rpc_login: String,
+    /// Wallet file to open on startup, mutually exclusive with `wallet_dir`.
+    pub wallet_file: String,
+    /// Password of `wallet_file`.
+    pub password: String,
+    /// Daemon RPC credentials as `username:password`.
+    pub daemon_login: String,
+    /// Some to pass `--trusted-daemon` or `--untrusted-daemon`, none to let
+    /// the wallet decide.
+    pub trusted_daemon: Option<bool>,
+    /// Command run for each new transaction, `%s` is replaced by the tx hash.
+    pub tx_notify: String,
+    pub shared_ringdb_dir: String,
+    pub max_concurrency: u32,
+    pub network: NetworkType,
+    pub rpc_ssl: Option<SslMode>,
+    pub rpc_ssl_private_key: String,
+    pub rpc_ssl_certificate: String,
+    pub rpc_ssl_ca_certificates: String,
+    pub rpc_ssl_allowed_fingerprints: Vec<String>,
+    pub rpc_ssl_allow_any_cert: bool,
+    pub daemon_ssl: Option<SslMode>,
+    /// CA certificates the daemon's certificate must be signed by.
+    pub daemon_ssl_ca_certificates: String,
+    /// Raw flags appended as is, for options without a typed field.
+    pub extra_args: Vec<String>,
+}
+
+/// Whether an RPC server uses TLS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SslMode {
+    Enabled,
+    Disabled,
+    Autodetect,
+}
+
+impl SslMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Enabled => "enabled",
+            SslMode::Disabled => "disabled",
+            SslMode::Autodetect => "autodetect",
+        }
+    }
 }
 
 /// Sane defaults for a mainnet regtest instance.
@@ -150,6 +339,28 @@ impl Default for MonerodArgs {
             rpc_bind_port: MONEROD_RPC_PORT,
             fixed_difficulty: 1,
             data_dir: "/monero".to_string(),
+            p2p_bind_port: 0,
+            allow_local_ip: false,
+            add_exclusive_node: vec![],
+            network: NetworkType::Mainnet,
+            p2p_bind_ip: String::new(),
+            add_peer: vec![],
+            zmq_rpc_bind_ip: String::new(),
+            zmq_rpc_bind_port: 0,
+            zmq_pub: String::new(),
+            no_zmq: false,
+            log_level: 0,
+            log_categories: vec![],
+            block_notify: String::new(),
+            db_sync_mode: None,
+            max_txpool_weight: 0,
+            prune_blockchain: false,
+            restricted_rpc: false,
+            rpc_login: String::new(),
+            rpc_ssl: None,
+            rpc_ssl_private_key: String::new(),
+            rpc_ssl_certificate: String::new(),
+            extra_args: vec![],
         }
     }
 }
@@ -165,13 +376,31 @@ impl Default for WalletArgs {
             rpc_bind_port: WALLET_RPC_PORT,
             daemon_address,
             log_level: 4,
+            rpc_login: String::new(),
+            wallet_file: String::new(),
+            password: String::new(),
+            daemon_login: String::new(),
+            trusted_daemon: None,
+            tx_notify: String::new(),
+            shared_ringdb_dir: String::new(),
+            max_concurrency: 0,
+            network: NetworkType::Mainnet,
+            rpc_ssl: None,
+            rpc_ssl_private_key: String::new(),
+            rpc_ssl_certificate: String::new(),
+            rpc_ssl_ca_certificates: String::new(),
+            rpc_ssl_allowed_fingerprints: vec![],
+            rpc_ssl_allow_any_cert: false,
+            daemon_ssl: None,
+            daemon_ssl_ca_certificates: String::new(),
+            extra_args: vec![],
         }
     }
 }
 
 impl MonerodArgs {
-    // Return monerod args as is single string so we can pass it to bash.
-    fn args(&self) -> String {
+    /// Return monerod argv, each flag and each value a separate argument.
+    pub(crate) fn args(&self) -> Vec<String> {
         let mut args = vec!["monerod".to_string()];
 
         if self.regtest {
@@ -203,28 +432,134 @@ impl MonerodArgs {
         }
 
         if !self.rpc_bind_ip.is_empty() {
-            args.push(format!("--rpc-bind-ip {}", self.rpc_bind_ip));
+            push_value(&mut args, "--rpc-bind-ip", &self.rpc_bind_ip);
         }
 
         if self.rpc_bind_port != 0 {
-            args.push(format!("--rpc-bind-port {}", self.rpc_bind_port));
+            push_value(&mut args, "--rpc-bind-port", self.rpc_bind_port);
         }
 
         if !self.data_dir.is_empty() {
-            args.push(format!("--data-dir {}", self.data_dir));
+            push_value(&mut args, "--data-dir", &self.data_dir);
         }
 
         if self.fixed_difficulty != 0 {
-            args.push(format!("--fixed-difficulty {}", self.fixed_difficulty));
+            push_value(&mut args, "--fixed-difficulty", self.fixed_difficulty);
+        }
+
+        if self.p2p_bind_port != 0 {
+            push_value(&mut args, "--p2p-bind-port", self.p2p_bind_port);
+        }
+
+        if self.allow_local_ip {
+            args.push("--allow-local-ip".to_string())
+        }
+
+        for node in &self.add_exclusive_node {
+            push_value(&mut args, "--add-exclusive-node", node);
+        }
+
+        match self.network {
+            NetworkType::Mainnet => {}
+            NetworkType::Testnet => args.push("--testnet".to_string()),
+            NetworkType::Stagenet => args.push("--stagenet".to_string()),
         }
 
-        args.join(" ")
+        if !self.p2p_bind_ip.is_empty() {
+            push_value(&mut args, "--p2p-bind-ip", &self.p2p_bind_ip);
+        }
+
+        for peer in &self.add_peer {
+            push_value(&mut args, "--add-peer", peer);
+        }
+
+        if !self.zmq_rpc_bind_ip.is_empty() {
+            push_value(&mut args, "--zmq-rpc-bind-ip", &self.zmq_rpc_bind_ip);
+        }
+
+        if self.zmq_rpc_bind_port != 0 {
+            push_value(&mut args, "--zmq-rpc-bind-port", self.zmq_rpc_bind_port);
+        }
+
+        if !self.zmq_pub.is_empty() {
+            push_value(&mut args, "--zmq-pub", &self.zmq_pub);
+        }
+
+        if self.no_zmq {
+            args.push("--no-zmq".to_string())
+        }
+
+        if !self.log_categories.is_empty() {
+            push_value(&mut args, "--log-level", self.log_categories.join(","));
+        } else if self.log_level != 0 {
+            push_value(&mut args, "--log-level", self.log_level);
+        }
+
+        if !self.block_notify.is_empty() {
+            push_value(&mut args, "--block-notify", &self.block_notify);
+        }
+
+        if let Some(mode) = self.db_sync_mode {
+            push_value(&mut args, "--db-sync-mode", mode.as_str());
+        }
+
+        if self.max_txpool_weight != 0 {
+            push_value(&mut args, "--max-txpool-weight", self.max_txpool_weight);
+        }
+
+        if self.prune_blockchain {
+            args.push("--prune-blockchain".to_string())
+        }
+
+        if self.restricted_rpc {
+            args.push("--restricted-rpc".to_string())
+        }
+
+        if !self.rpc_login.is_empty() {
+            push_value(&mut args, "--rpc-login", &self.rpc_login);
+        }
+
+        if let Some(mode) = self.rpc_ssl {
+            push_value(&mut args, "--rpc-ssl", mode.as_str());
+        }
+
+        if !self.rpc_ssl_private_key.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-private-key",
+                &self.rpc_ssl_private_key,
+            );
+        }
+
+        if !self.rpc_ssl_certificate.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-certificate",
+                &self.rpc_ssl_certificate,
+            );
+        }
+
+        args.extend(self.extra_args.iter().cloned());
+
+        args
+    }
+
+    /// Enables TLS on the RPC with the certificate and key written by
+    /// `Certificate::write_to()` to `dir`.
+    pub(crate) fn with_rpc_ssl(self, dir: &str) -> Self {
+        MonerodArgs {
+            rpc_ssl: Some(SslMode::Enabled),
+            rpc_ssl_private_key: join(dir, PRIVATE_KEY_FILE),
+            rpc_ssl_certificate: join(dir, CERTIFICATE_FILE),
+            ..self
+        }
     }
 }
 
 impl WalletArgs {
-    // Return monero-wallet-rpc args as is single string so we can pass it to bash.
-    fn args(&self) -> String {
+    /// Return monero-wallet-rpc argv, each flag and each value a separate
+    /// argument.
+    pub(crate) fn args(&self) -> Vec<String> {
         let mut args = vec!["monero-wallet-rpc".to_string()];
 
         if self.disable_rpc_login {
@@ -236,42 +571,615 @@ impl WalletArgs {
         }
 
         if !self.wallet_dir.is_empty() {
-            args.push(format!("--wallet-dir {}", self.wallet_dir));
+            push_value(&mut args, "--wallet-dir", &self.wallet_dir);
         }
 
         if !self.rpc_bind_ip.is_empty() {
-            args.push(format!("--rpc-bind-ip {}", self.rpc_bind_ip));
+            push_value(&mut args, "--rpc-bind-ip", &self.rpc_bind_ip);
         }
 
         if self.rpc_bind_port != 0 {
-            args.push(format!("--rpc-bind-port {}", self.rpc_bind_port));
+            push_value(&mut args, "--rpc-bind-port", self.rpc_bind_port);
         }
 
         if !self.daemon_address.is_empty() {
-            args.push(format!("--daemon-address {}", self.daemon_address));
+            push_value(&mut args, "--daemon-address", &self.daemon_address);
         }
 
         if self.log_level != 0 {
-            args.push(format!("--log-level {}", self.log_level));
+            push_value(&mut args, "--log-level", self.log_level);
+        }
+
+        if !self.rpc_login.is_empty() {
+            push_value(&mut args, "--rpc-login", &self.rpc_login);
+        }
+
+        if !self.wallet_file.is_empty() {
+            push_value(&mut args, "--wallet-file", &self.wallet_file);
+        }
+
+        if !self.password.is_empty() {
+            push_value(&mut args, "--password", &self.password);
+        }
+
+        if !self.daemon_login.is_empty() {
+            push_value(&mut args, "--daemon-login", &self.daemon_login);
+        }
+
+        match self.trusted_daemon {
+            Some(true) => args.push("--trusted-daemon".to_string()),
+            Some(false) => args.push("--untrusted-daemon".to_string()),
+            None => {}
+        }
+
+        if !self.tx_notify.is_empty() {
+            push_value(&mut args, "--tx-notify", &self.tx_notify);
+        }
+
+        if !self.shared_ringdb_dir.is_empty() {
+            push_value(&mut args, "--shared-ringdb-dir", &self.shared_ringdb_dir);
         }
 
-        args.join(" ")
+        if self.max_concurrency != 0 {
+            push_value(&mut args, "--max-concurrency", self.max_concurrency);
+        }
+
+        match self.network {
+            NetworkType::Mainnet => {}
+            NetworkType::Testnet => args.push("--testnet".to_string()),
+            NetworkType::Stagenet => args.push("--stagenet".to_string()),
+        }
+
+        if let Some(mode) = self.rpc_ssl {
+            push_value(&mut args, "--rpc-ssl", mode.as_str());
+        }
+
+        if !self.rpc_ssl_private_key.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-private-key",
+                &self.rpc_ssl_private_key,
+            );
+        }
+
+        if !self.rpc_ssl_certificate.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-certificate",
+                &self.rpc_ssl_certificate,
+            );
+        }
+
+        if !self.rpc_ssl_ca_certificates.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-ca-certificates",
+                &self.rpc_ssl_ca_certificates,
+            );
+        }
+
+        if !self.rpc_ssl_allowed_fingerprints.is_empty() {
+            push_value(
+                &mut args,
+                "--rpc-ssl-allowed-fingerprints",
+                self.rpc_ssl_allowed_fingerprints.join(","),
+            );
+        }
+
+        if self.rpc_ssl_allow_any_cert {
+            args.push("--rpc-ssl-allow-any-cert".to_string())
+        }
+
+        if let Some(mode) = self.daemon_ssl {
+            push_value(&mut args, "--daemon-ssl", mode.as_str());
+        }
+
+        if !self.daemon_ssl_ca_certificates.is_empty() {
+            push_value(
+                &mut args,
+                "--daemon-ssl-ca-certificates",
+                &self.daemon_ssl_ca_certificates,
+            );
+        }
+
+        args.extend(self.extra_args.iter().cloned());
+
+        args
+    }
+
+    /// Checks for options monero-wallet-rpc would reject or ignore together.
+    pub fn validate(&self) -> Result<()> {
+        if !self.wallet_dir.is_empty() && !self.wallet_file.is_empty() {
+            bail!("wallet_dir and wallet_file are mutually exclusive");
+        }
+
+        if !self.password.is_empty() && self.wallet_file.is_empty() {
+            bail!("password requires wallet_file");
+        }
+
+        if self.disable_rpc_login && !self.rpc_login.is_empty() {
+            bail!("rpc_login requires disable_rpc_login to be false");
+        }
+
+        for (name, login) in &[
+            ("rpc_login", &self.rpc_login),
+            ("daemon_login", &self.daemon_login),
+        ] {
+            if !login.is_empty() && !login.contains(':') {
+                bail!("{} must be username:password", name);
+            }
+        }
+
+        if self.rpc_ssl_private_key.is_empty() != self.rpc_ssl_certificate.is_empty() {
+            bail!("rpc_ssl_private_key and rpc_ssl_certificate must be set together");
+        }
+
+        let ssl_options = !self.rpc_ssl_private_key.is_empty()
+            || !self.rpc_ssl_ca_certificates.is_empty()
+            || !self.rpc_ssl_allowed_fingerprints.is_empty()
+            || self.rpc_ssl_allow_any_cert;
+        if ssl_options && self.rpc_ssl == Some(SslMode::Disabled) {
+            bail!("rpc_ssl options are set but rpc_ssl is disabled");
+        }
+
+        if !self.daemon_ssl_ca_certificates.is_empty() && self.daemon_ssl == Some(SslMode::Disabled)
+        {
+            bail!("daemon_ssl_ca_certificates is set but daemon_ssl is disabled");
+        }
+
+        Ok(())
+    }
+
+    /// Enables TLS on the RPC and to the daemon with the certificate and key
+    /// written by `Certificate::write_to()` to `dir`, the daemon must use the
+    /// same certificate.
+    pub(crate) fn with_rpc_ssl(self, dir: &str) -> Self {
+        let certificate = join(dir, CERTIFICATE_FILE);
+        WalletArgs {
+            rpc_ssl: Some(SslMode::Enabled),
+            rpc_ssl_private_key: join(dir, PRIVATE_KEY_FILE),
+            rpc_ssl_certificate: certificate.clone(),
+            daemon_ssl: Some(SslMode::Enabled),
+            daemon_ssl_ca_certificates: certificate,
+            ..self
+        }
     }
 }
 
+fn join(dir: &str, file: &str) -> String {
+    Path::new(dir).join(file).display().to_string()
+}
+
+fn push_value<T: ToString>(args: &mut Vec<String>, flag: &str, value: T) {
+    args.push(flag.to_string());
+    args.push(value.to_string());
+}
+
+/// Starts the commands passed as positional parameters in the background and
+/// waits for all of them. Each command is preceded by its number of
+/// arguments, so values are never interpreted by the shell.
+const SUPERVISOR: &str =
+    r#"while [ $# -gt 0 ]; do n=$1; shift; "${@:1:$n}" & shift "$n"; done; wait"#;
+
 impl IntoIterator for Args {
     type Item = String;
     type IntoIter = ::std::vec::IntoIter<String>;
 
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
-        let mut args = Vec::new();
+        let mut args = vec![
+            "/bin/bash".to_string(),
+            "-c".to_string(),
+            SUPERVISOR.to_string(),
+            // $0 of the supervisor script.
+            "supervisor".to_string(),
+        ];
 
-        args.push("/bin/bash".into());
-        args.push("-c".into());
+        let login = self.wallet_rpc_login;
+        let ssl_dir = self.rpc_ssl_dir;
 
-        let cmd = format!("{} & {}", self.monerod.args(), self.wallet.args());
-        args.push(cmd);
+        let mut monerod = self.monerod;
+        if !ssl_dir.is_empty() {
+            monerod = monerod.with_rpc_ssl(&ssl_dir);
+        }
+        let mut cmds = vec![monerod.args()];
+        cmds.extend(self.wallets.into_iter().map(|mut wallet| {
+            if !login.is_empty() {
+                wallet = WalletArgs {
+                    disable_rpc_login: false,
+                    rpc_login: login.clone(),
+                    ..wallet
+                };
+            }
+            if !ssl_dir.is_empty() {
+                wallet = wallet.with_rpc_ssl(&ssl_dir);
+            }
+            wallet.args()
+        }));
+        for cmd in cmds {
+            args.push(cmd.len().to_string());
+            args.extend(cmd);
+        }
 
         args.into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn default_monerod_argv() {
+        let got = MonerodArgs::default().args();
+
+        let want = strings(&[
+            "monerod",
+            "--regtest",
+            "--offline",
+            "--rpc-payment-allow-free-loopback",
+            "--confirm-external-bind",
+            "--non-interactive",
+            "--no-igd",
+            "--hide-my-port",
+            "--rpc-bind-ip",
+            "0.0.0.0",
+            "--rpc-bind-port",
+            "48081",
+            "--data-dir",
+            "/monero",
+            "--fixed-difficulty",
+            "1",
+        ]);
+
+        assert_that!(got).is_equal_to(want);
+    }
+
+    #[test]
+    fn monerod_argv_with_exclusive_nodes() {
+        let args = MonerodArgs {
+            offline: false,
+            allow_local_ip: true,
+            p2p_bind_port: MONEROD_P2P_PORT,
+            add_exclusive_node: vec!["node-1:48080".to_string(), "node-2:48080".to_string()],
+            ..MonerodArgs::default()
+        };
+
+        let got = args.args();
+
+        assert!(!got.contains(&"--offline".to_string()));
+        assert_that!(got[got.len() - 7..].to_vec()).is_equal_to(strings(&[
+            "--p2p-bind-port",
+            "48080",
+            "--allow-local-ip",
+            "--add-exclusive-node",
+            "node-1:48080",
+            "--add-exclusive-node",
+            "node-2:48080",
+        ]));
+    }
+
+    #[test]
+    fn monerod_argv_for_production_config() {
+        let args = MonerodArgs {
+            regtest: false,
+            offline: false,
+            rpc_payment_allow_free_loopback: false,
+            fixed_difficulty: 0,
+            data_dir: String::new(),
+            network: NetworkType::Stagenet,
+            p2p_bind_ip: "0.0.0.0".to_string(),
+            p2p_bind_port: 38080,
+            add_peer: vec!["seed.example.org:38080".to_string()],
+            zmq_rpc_bind_ip: "127.0.0.1".to_string(),
+            zmq_rpc_bind_port: 38082,
+            zmq_pub: "tcp://127.0.0.1:38083".to_string(),
+            log_level: 1,
+            log_categories: vec!["*:WARN".to_string(), "net.p2p:DEBUG".to_string()],
+            block_notify: "/usr/bin/notify %s".to_string(),
+            db_sync_mode: Some(DbSyncMode::Safe),
+            max_txpool_weight: 648_000_000,
+            prune_blockchain: true,
+            restricted_rpc: true,
+            rpc_login: "user:pass".to_string(),
+            extra_args: vec!["--disable-dns-checkpoints".to_string()],
+            ..MonerodArgs::default()
+        };
+
+        let got = args.args();
+
+        let want = strings(&[
+            "monerod",
+            "--confirm-external-bind",
+            "--non-interactive",
+            "--no-igd",
+            "--hide-my-port",
+            "--rpc-bind-ip",
+            "0.0.0.0",
+            "--rpc-bind-port",
+            "48081",
+            "--p2p-bind-port",
+            "38080",
+            "--stagenet",
+            "--p2p-bind-ip",
+            "0.0.0.0",
+            "--add-peer",
+            "seed.example.org:38080",
+            "--zmq-rpc-bind-ip",
+            "127.0.0.1",
+            "--zmq-rpc-bind-port",
+            "38082",
+            "--zmq-pub",
+            "tcp://127.0.0.1:38083",
+            "--log-level",
+            "*:WARN,net.p2p:DEBUG",
+            "--block-notify",
+            "/usr/bin/notify %s",
+            "--db-sync-mode",
+            "safe",
+            "--max-txpool-weight",
+            "648000000",
+            "--prune-blockchain",
+            "--restricted-rpc",
+            "--rpc-login",
+            "user:pass",
+            "--disable-dns-checkpoints",
+        ]);
+
+        assert_that!(got).is_equal_to(want);
+    }
+
+    #[test]
+    fn wallet_argv_keeps_values_with_spaces_and_shell_characters() {
+        let args = WalletArgs {
+            wallet_dir: "/tmp/my wallets; rm -rf /".to_string(),
+            daemon_address: "$(reboot)".to_string(),
+            ..WalletArgs::default()
+        };
+
+        let got = args.args();
+
+        let want = strings(&[
+            "monero-wallet-rpc",
+            "--disable-rpc-login",
+            "--confirm-external-bind",
+            "--wallet-dir",
+            "/tmp/my wallets; rm -rf /",
+            "--rpc-bind-ip",
+            "0.0.0.0",
+            "--rpc-bind-port",
+            "48083",
+            "--daemon-address",
+            "$(reboot)",
+            "--log-level",
+            "4",
+        ]);
+
+        assert_that!(got).is_equal_to(want);
+    }
+
+    #[test]
+    fn wallet_argv_for_production_config() {
+        let args = WalletArgs {
+            disable_rpc_login: false,
+            wallet_dir: String::new(),
+            log_level: 0,
+            rpc_login: "user:pass".to_string(),
+            wallet_file: "/wallets/hot".to_string(),
+            password: "secret".to_string(),
+            daemon_login: "daemon:pass".to_string(),
+            trusted_daemon: Some(false),
+            tx_notify: "/usr/bin/notify %s".to_string(),
+            shared_ringdb_dir: "/ringdb".to_string(),
+            max_concurrency: 2,
+            network: NetworkType::Testnet,
+            rpc_ssl: Some(SslMode::Enabled),
+            rpc_ssl_private_key: "/tls/key.pem".to_string(),
+            rpc_ssl_certificate: "/tls/cert.pem".to_string(),
+            rpc_ssl_allowed_fingerprints: vec!["aa:bb".to_string(), "cc:dd".to_string()],
+            extra_args: vec!["--no-initial-sync".to_string()],
+            ..WalletArgs::default()
+        };
+        args.validate().expect("valid config");
+
+        let got = args.args();
+
+        let want = strings(&[
+            "monero-wallet-rpc",
+            "--confirm-external-bind",
+            "--rpc-bind-ip",
+            "0.0.0.0",
+            "--rpc-bind-port",
+            "48083",
+            "--daemon-address",
+            "localhost:48081",
+            "--rpc-login",
+            "user:pass",
+            "--wallet-file",
+            "/wallets/hot",
+            "--password",
+            "secret",
+            "--daemon-login",
+            "daemon:pass",
+            "--untrusted-daemon",
+            "--tx-notify",
+            "/usr/bin/notify %s",
+            "--shared-ringdb-dir",
+            "/ringdb",
+            "--max-concurrency",
+            "2",
+            "--testnet",
+            "--rpc-ssl",
+            "enabled",
+            "--rpc-ssl-private-key",
+            "/tls/key.pem",
+            "--rpc-ssl-certificate",
+            "/tls/cert.pem",
+            "--rpc-ssl-allowed-fingerprints",
+            "aa:bb,cc:dd",
+            "--no-initial-sync",
+        ]);
+
+        assert_that!(got).is_equal_to(want);
+    }
+
+    #[test]
+    fn rejects_inconsistent_wallet_args() {
+        assert!(WalletArgs::default().validate().is_ok());
+
+        let both_dir_and_file = WalletArgs {
+            wallet_file: "/wallets/hot".to_string(),
+            ..WalletArgs::default()
+        };
+        assert!(both_dir_and_file.validate().is_err());
+
+        let password_without_file = WalletArgs {
+            password: "secret".to_string(),
+            ..WalletArgs::default()
+        };
+        assert!(password_without_file.validate().is_err());
+
+        let login_while_disabled = WalletArgs {
+            rpc_login: "user:pass".to_string(),
+            ..WalletArgs::default()
+        };
+        assert!(login_while_disabled.validate().is_err());
+
+        let key_without_certificate = WalletArgs {
+            rpc_ssl_private_key: "/tls/key.pem".to_string(),
+            ..WalletArgs::default()
+        };
+        assert!(key_without_certificate.validate().is_err());
+
+        let options_while_disabled = WalletArgs {
+            rpc_ssl: Some(SslMode::Disabled),
+            rpc_ssl_allow_any_cert: true,
+            ..WalletArgs::default()
+        };
+        assert!(options_while_disabled.validate().is_err());
+    }
+
+    #[test]
+    fn supervisor_argv_prefixes_each_command_with_its_length() {
+        let args = Args {
+            monerod: MonerodArgs::default(),
+            wallets: vec![WalletArgs::default(), WalletArgs {
+                rpc_bind_port: WALLET_RPC_PORT + 1,
+                ..WalletArgs::default()
+            }],
+            wallet_rpc_login: String::new(),
+            rpc_ssl_dir: String::new(),
+        };
+        let monerod = MonerodArgs::default().args();
+        let wallet = WalletArgs::default().args();
+
+        let got: Vec<String> = args.into_iter().collect();
+
+        assert_that!(got[..4].to_vec()).is_equal_to(vec![
+            "/bin/bash".to_string(),
+            "-c".to_string(),
+            SUPERVISOR.to_string(),
+            "supervisor".to_string(),
+        ]);
+        assert_that!(got[4].clone()).is_equal_to(monerod.len().to_string());
+        assert_that!(got[5..5 + monerod.len()].to_vec()).is_equal_to(monerod.clone());
+
+        let second = 5 + monerod.len();
+        assert_that!(got[second].clone()).is_equal_to(wallet.len().to_string());
+        assert_that!(got[second + 1..second + 1 + wallet.len()].to_vec())
+            .is_equal_to(wallet.clone());
+
+        let third = second + 1 + wallet.len();
+        let extra = got[third + 1..].to_vec();
+        let port = extra
+            .iter()
+            .position(|arg| arg == "--rpc-bind-port")
+            .expect("no --rpc-bind-port for the extra wallet");
+        assert_that!(extra[port + 1].clone()).is_equal_to((WALLET_RPC_PORT + 1).to_string());
+        assert_that!(extra.len()).is_equal_to(wallet.len());
+    }
+
+    #[test]
+    fn image_runs_monerod_with_given_args() {
+        let image = Monero::default().with_monerod_args(MonerodArgs {
+            fixed_difficulty: 10,
+            ..MonerodArgs::default()
+        });
+
+        let got: Vec<String> = image.args().into_iter().collect();
+        let difficulty = got
+            .iter()
+            .position(|arg| arg == "--fixed-difficulty")
+            .expect("no --fixed-difficulty");
+
+        assert_that!(got[difficulty + 1].clone()).is_equal_to("10".to_string());
+    }
+
+    #[test]
+    fn image_runs_default_wallet_with_given_args() {
+        let image = Monero::default()
+            .with_default_wallet_args(WalletArgs {
+                max_concurrency: 2,
+                ..WalletArgs::default()
+            })
+            .unwrap()
+            .with_wallet(WALLET_RPC_PORT + 1);
+
+        let got: Vec<String> = image.args().into_iter().collect();
+        let wallets = got.iter().filter(|arg| *arg == "monero-wallet-rpc").count();
+        let concurrency = got.iter().filter(|arg| *arg == "--max-concurrency").count();
+
+        assert_that!(wallets).is_equal_to(2);
+        assert_that!(concurrency).is_equal_to(1);
+
+        let password_without_file = WalletArgs {
+            password: "secret".to_string(),
+            ..WalletArgs::default()
+        };
+        assert!(Monero::default()
+            .with_default_wallet_args(password_without_file)
+            .is_err());
+    }
+
+    #[test]
+    fn rpc_ssl_enables_tls_on_every_rpc() {
+        let image = Monero::default()
+            .with_wallet(WALLET_RPC_PORT + 1)
+            .with_rpc_ssl("/tmp/certs");
+
+        assert_that!(image.volumes().get("/tmp/certs")).is_equal_to(Some(&RPC_SSL_DIR.to_string()));
+
+        let got: Vec<String> = image.args().into_iter().collect();
+        let ssl = strings(&[
+            "--rpc-ssl",
+            "enabled",
+            "--rpc-ssl-private-key",
+            "/rpc-ssl/rpc.key",
+            "--rpc-ssl-certificate",
+            "/rpc-ssl/rpc.crt",
+        ]);
+        let daemon_ssl = strings(&[
+            "--daemon-ssl",
+            "enabled",
+            "--daemon-ssl-ca-certificates",
+            "/rpc-ssl/rpc.crt",
+        ]);
+
+        let monerods = got.iter().filter(|arg| *arg == "monerod").count();
+        let wallets = got.iter().filter(|arg| *arg == "monero-wallet-rpc").count();
+        let rpc_ssl = got.windows(ssl.len()).filter(|w| *w == &ssl[..]).count();
+        let wallet_daemon_ssl = got
+            .windows(daemon_ssl.len())
+            .filter(|w| *w == &daemon_ssl[..])
+            .count();
+
+        assert_that!(monerods).is_equal_to(1);
+        assert_that!(wallets).is_equal_to(2);
+        assert_that!(rpc_ssl).is_equal_to(monerods + wallets);
+        assert_that!(wallet_daemon_ssl).is_equal_to(wallets);
+    }
+}
@@ -15,35 +15,59 @@
 //! # monero-harness
 //!
 //! A simple lib to start a monero container (incl. monerod and
-//! monero-wallet-rpc). Provides initialisation methods to generate blocks,
-//! create and fund accounts, and start a continuous mining task mining blocks
-//! every BLOCK_TIME_SECS seconds.
+//! monero-wallet-rpc), or the local binaries where Docker is not available.
+//! Provides initialisation methods to generate blocks, create and fund
+//! accounts, and start a continuous mining task mining blocks every
+//! BLOCK_TIME_SECS seconds.
 //!
-//! Also provides standalone JSON RPC clients for monerod and monero-wallet-rpc.
+//! Also provides standalone JSON RPC clients for monerod and monero-wallet-rpc,
+//! and a multi-node regtest network of peered monerod nodes.
 
+pub mod backend;
+#[cfg(feature = "cassette")]
+pub mod cassette;
 pub mod image;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod network;
+#[cfg(feature = "proxy")]
+pub mod proxy;
 pub mod rpc;
-
-use anyhow::Result;
-use rand::Rng;
-use serde::Deserialize;
-use std::time::Duration;
-use testcontainers::{clients::Cli, core::Port, Container, Docker};
+#[cfg(any(feature = "mock", feature = "proxy"))]
+mod server;
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, convert::TryFrom, path::Path, time::Duration};
+use testcontainers::{clients::Cli, Container};
 use tokio::time;
 
 use crate::{
-    image::{MONEROD_RPC_PORT, WALLET_RPC_PORT},
+    backend::Backend,
+    image::{MonerodArgs, WalletArgs},
     rpc::{
         monerod,
-        wallet::{self, GetAddress, Transfer},
+        wallet::{
+            self, GetAddress, IncomingTransfer, KeyType, Priority, Refreshed, Transfer,
+            TransferType,
+        },
+        Certificate, ClientConfig, Credentials, Tls,
     },
 };
 
 /// How often we mine a block.
 const BLOCK_TIME_SECS: u64 = 1;
 
-/// Poll interval when checking if the wallet has synced with monerod.
-const WAIT_WALLET_SYNC_MILLIS: u64 = 1000;
+/// Wallet file the miner mines to, its sub-accounts are used for Alice and Bob.
+const MINER_WALLET: &str = "miner_wallet";
+
+/// Ring size used by monero-wallet-rpc v0.16.
+const RING_SIZE: u32 = 11;
+
+/// Wallet files used by `Monero::init_cold_signing()`.
+const WATCH_WALLET: &str = "watch_wallet";
+const SIGNING_WALLET: &str = "signing_wallet";
 
 /// Wallet sub-account indecies.
 const ACCOUNT_INDEX_PRIMARY: u32 = 0;
@@ -52,45 +76,199 @@ const ACCOUNT_INDEX_BOB: u32 = 2;
 
 #[derive(Debug)]
 pub struct Monero<'c> {
-    pub docker: Container<'c, Cli, image::Monero>,
+    /// Runs monerod and the monero-wallet-rpc instances.
+    pub backend: Box<dyn Backend + 'c>,
     pub monerod_rpc_port: u16,
+    /// Port of the monero-wallet-rpc holding the miner wallet.
     pub wallet_rpc_port: u16,
+    /// Ports of the additional monero-wallet-rpc instances, by wallet name.
+    pub wallet_rpc_ports: HashMap<String, u16>,
+    /// Credentials of the monero-wallet-rpc instances if started with login.
+    pub wallet_rpc_login: Option<Credentials>,
+    /// Certificate of monerod and the monero-wallet-rpc instances if started
+    /// with TLS.
+    pub rpc_certificate: Option<Certificate>,
+    /// HTTP client settings of the RPC clients, see `with_client_config()`.
+    pub client_config: ClientConfig,
 }
 
 impl<'c> Monero<'c> {
     /// Starts a new regtest monero container.
     pub fn new(cli: &'c Cli) -> Self {
-        let mut rng = rand::thread_rng();
-        let monerod_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
-        let wallet_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
-
-        let image = image::Monero::default()
-            .with_mapped_port(Port {
-                local: monerod_rpc_port,
-                internal: MONEROD_RPC_PORT,
-            })
-            .with_mapped_port(Port {
-                local: wallet_rpc_port,
-                internal: WALLET_RPC_PORT,
-            });
-
-        println!("running image ...");
-        let docker = cli.run(image);
-        println!("image ran");
+        Self::new_with_wallets(cli, &[])
+    }
+
+    /// Starts a new regtest monero container with an additional, independent
+    /// monero-wallet-rpc for each name in `wallets`. Each wallet-rpc gets its
+    /// own port and, once `init_wallet()` is called, its own wallet file.
+    pub fn new_with_wallets(cli: &'c Cli, wallets: &[&str]) -> Self {
+        Self::new_with_args(cli, wallets, MonerodArgs::default(), WalletArgs::default())
+            .expect("default args are valid")
+    }
+
+    /// Like `new_with_wallets()` but monerod runs with `monerod` and every
+    /// monero-wallet-rpc with `wallet`, except for the RPC bind addresses
+    /// and ports the container needs and the wallet-rpcs' daemon address.
+    /// Fails if `wallet` is inconsistent.
+    pub fn new_with_args(
+        cli: &'c Cli,
+        wallets: &[&str],
+        monerod: MonerodArgs,
+        wallet: WalletArgs,
+    ) -> Result<Self> {
+        let backend = backend::Docker::start(cli, wallets, monerod, wallet, None, None)?;
+        Ok(Self::from_backend(Box::new(backend)))
+    }
+
+    /// Like `new_with_wallets()` but every monero-wallet-rpc requires HTTP
+    /// digest authentication with `login`, the clients returned by
+    /// `wallet_rpc_client()` and `wallet()` use it.
+    pub fn new_with_login(cli: &'c Cli, wallets: &[&str], login: Credentials) -> Self {
+        let backend = backend::Docker::start(
+            cli,
+            wallets,
+            MonerodArgs::default(),
+            WalletArgs::default(),
+            Some(login),
+            None,
+        )
+        .expect("default args are valid");
+        Self::from_backend(Box::new(backend))
+    }
+
+    /// Like `new_with_wallets()` but monerod and every monero-wallet-rpc
+    /// serve RPC over TLS with a freshly generated self-signed certificate,
+    /// the RPC clients trust it.
+    pub fn new_with_tls(cli: &'c Cli, wallets: &[&str]) -> Result<Self> {
+        let certificate = Certificate::generate()?;
+        let backend = backend::Docker::start(
+            cli,
+            wallets,
+            MonerodArgs::default(),
+            WalletArgs::default(),
+            None,
+            Some(certificate),
+        )?;
+        Ok(Self::from_backend(Box::new(backend)))
+    }
+
+    /// Starts regtest monerod and monero-wallet-rpc from the binaries in
+    /// `bin_dir` instead of a container, with an additional monero-wallet-rpc
+    /// for each name in `wallets`.
+    pub fn new_local(bin_dir: impl AsRef<Path>, wallets: &[&str]) -> Result<Self> {
+        Self::new_local_with_args(
+            bin_dir,
+            wallets,
+            MonerodArgs::default(),
+            WalletArgs::default(),
+        )
+    }
+
+    /// Like `new_local()` but monerod runs with `monerod` and every
+    /// monero-wallet-rpc with `wallet`, except for the ports, RPC bind
+    /// addresses and directories the harness picks.
+    pub fn new_local_with_args(
+        bin_dir: impl AsRef<Path>,
+        wallets: &[&str],
+        monerod: MonerodArgs,
+        wallet: WalletArgs,
+    ) -> Result<Self> {
+        let backend = backend::Local::start(bin_dir, wallets, monerod, wallet, None, None)?;
+        Ok(Self::from_backend(Box::new(backend)))
+    }
+
+    /// Like `new_local()` but with TLS as in `new_with_tls()`.
+    pub fn new_local_with_tls(bin_dir: impl AsRef<Path>, wallets: &[&str]) -> Result<Self> {
+        let certificate = Certificate::generate()?;
+        let backend = backend::Local::start(
+            bin_dir,
+            wallets,
+            MonerodArgs::default(),
+            WalletArgs::default(),
+            None,
+            Some(certificate),
+        )?;
+        Ok(Self::from_backend(Box::new(backend)))
+    }
 
+    /// Uses an already running `backend`.
+    pub fn from_backend(backend: Box<dyn Backend + 'c>) -> Self {
         Self {
-            docker,
-            monerod_rpc_port,
-            wallet_rpc_port,
+            monerod_rpc_port: backend.monerod_rpc_port(),
+            wallet_rpc_port: backend.wallet_rpc_port(),
+            wallet_rpc_ports: backend.wallet_rpc_ports(),
+            wallet_rpc_login: backend.wallet_rpc_login(),
+            rpc_certificate: backend.rpc_certificate(),
+            client_config: ClientConfig::default(),
+            backend,
         }
     }
 
+    /// The container running monerod and monero-wallet-rpc, none if not run
+    /// in Docker. Takes the place of the former public `docker` field.
+    pub fn docker(&self) -> Option<&Container<'_, Cli, image::Monero>> {
+        self.backend.container()
+    }
+
+    /// Uses `config` for the clients returned by `monerod_rpc_client()`,
+    /// `wallet_rpc_client()` and `wallet()`, and for the calls the harness
+    /// makes itself. `config.tls` is ignored, the harness trusts its own
+    /// certificate if started with TLS.
+    pub fn with_client_config(mut self, config: ClientConfig) -> Result<Self> {
+        self.client_config = config;
+        // Fail here rather than when a client is needed.
+        monerod::Client::with_config(self.rpc_url(self.monerod_rpc_port), self.rpc_config())?;
+
+        Ok(self)
+    }
+
     pub fn wallet_rpc_client(&self) -> wallet::Client {
-        wallet::Client::localhost(self.wallet_rpc_port)
+        self.wallet_client(self.wallet_rpc_port)
+    }
+
+    /// Client for the additional monero-wallet-rpc started for wallet `name`.
+    pub fn wallet(&self, name: &str) -> Result<wallet::Client> {
+        let port = self
+            .wallet_rpc_ports
+            .get(name)
+            .ok_or_else(|| anyhow!("no wallet-rpc started for wallet {}", name))?;
+
+        Ok(self.wallet_client(*port))
+    }
+
+    fn wallet_client(&self, port: u16) -> wallet::Client {
+        let client = wallet::Client::with_config(self.rpc_url(port), self.rpc_config())
+            .expect("client config is valid");
+        match &self.wallet_rpc_login {
+            Some(login) => client.with_credentials(login.clone()),
+            None => client,
+        }
     }
 
     pub fn monerod_rpc_client(&self) -> monerod::Client {
-        monerod::Client::localhost(self.monerod_rpc_port)
+        monerod::Client::with_config(self.rpc_url(self.monerod_rpc_port), self.rpc_config())
+            .expect("client config is valid")
+    }
+
+    /// JSON RPC endpoint of the daemon listening on local `port`.
+    fn rpc_url(&self, port: u16) -> Url {
+        let scheme = match self.rpc_certificate {
+            Some(_) => "https",
+            None => "http",
+        };
+        let url = format!("{}://127.0.0.1:{}/json_rpc", scheme, port);
+        Url::parse(&url).expect("url is well formed")
+    }
+
+    /// `client_config` trusting the harness certificate, if started with TLS.
+    fn rpc_config(&self) -> ClientConfig {
+        ClientConfig {
+            tls: self
+                .rpc_certificate
+                .as_ref()
+                .map(|certificate| Tls::CaCertificate(certificate.certificate.clone())),
+            ..self.client_config.clone()
+        }
     }
 
     /// Initialise by creating a wallet, generating some `blocks`, and starting
@@ -101,15 +279,15 @@ impl<'c> Monero<'c> {
         let wallet = self.wallet_rpc_client();
         let monerod = self.monerod_rpc_client();
 
-        wallet.create_wallet("miner_wallet").await?;
+        wallet.create_wallet(MINER_WALLET).await?;
 
         let alice = wallet.create_account("alice").await?;
         let bob = wallet.create_account("bob").await?;
 
         let miner = self.get_address_primary().await?.address;
 
-        let res = monerod.generate_blocks(70, &miner).await?;
-        self.wait_for_wallet_block_height(res.height).await?;
+        let _ = monerod.generate_blocks(70, &miner).await?;
+        self.sync_wallet().await?;
 
         if alice_funding > 0 {
             self.fund_account(&alice.address, &miner, alice_funding)
@@ -134,7 +312,7 @@ impl<'c> Monero<'c> {
         let wallet = self.wallet_rpc_client();
         let monerod = self.monerod_rpc_client();
 
-        wallet.create_wallet("miner_wallet").await?;
+        wallet.create_wallet(MINER_WALLET).await?;
         let miner = self.get_address_primary().await?.address;
 
         let _ = monerod.generate_blocks(blocks, &miner).await?;
@@ -144,41 +322,207 @@ impl<'c> Monero<'c> {
         Ok(())
     }
 
+    /// Sets up a `threshold`-of-`participants` multisig wallet, one wallet
+    /// file per participant, and funds it with `funding` from the primary
+    /// account. Requires `init()` to have been called, the miner wallet is
+    /// re-opened before returning. Fails if `threshold` is less than 2 or
+    /// more than `participants`.
+    pub async fn init_multisig(
+        &self,
+        threshold: u32,
+        participants: u32,
+        funding: u64,
+    ) -> Result<Multisig> {
+        if threshold < 2 || threshold > participants {
+            bail!(
+                "multisig threshold {} must be between 2 and the number of participants {}",
+                threshold,
+                participants
+            );
+        }
+
+        let wallet = self.wallet_rpc_client();
+
+        let wallets: Vec<String> = (0..participants)
+            .map(|i| format!("multisig_{}", i))
+            .collect();
+
+        let mut infos = Vec::new();
+        for name in &wallets {
+            wallet.create_wallet(name).await?;
+            infos.push(wallet.prepare_multisig().await?);
+        }
+
+        let mut address = String::new();
+        let mut round = Vec::new();
+        for (i, name) in wallets.iter().enumerate() {
+            wallet.open_wallet(name).await?;
+            let res = wallet.make_multisig(others(&infos, i), threshold).await?;
+            address = res.address;
+            round.push(res.multisig_info);
+        }
+        infos = round;
+
+        // M-of-N wallets with M < N need further rounds of key exchange.
+        while !wallet.is_multisig().await?.ready {
+            let mut round = Vec::new();
+            for (i, name) in wallets.iter().enumerate() {
+                wallet.open_wallet(name).await?;
+                let res = wallet.exchange_multisig_keys(others(&infos, i)).await?;
+                address = res.address;
+                round.push(res.multisig_info);
+            }
+            infos = round;
+        }
+
+        wallet.open_wallet(MINER_WALLET).await?;
+        let miner = self.get_address_primary().await?.address;
+        self.fund_account(&address, &miner, funding).await?;
+
+        // Participants must exchange multisig info before they can spend.
+        let mut infos = Vec::new();
+        for name in &wallets {
+            wallet.open_wallet(name).await?;
+            self.sync_wallet().await?;
+            infos.push(wallet.export_multisig_info().await?);
+        }
+        for (i, name) in wallets.iter().enumerate() {
+            wallet.open_wallet(name).await?;
+            wallet.import_multisig_info(others(&infos, i)).await?;
+        }
+
+        wallet.open_wallet(MINER_WALLET).await?;
+
+        Ok(Multisig {
+            address,
+            wallets,
+            threshold,
+        })
+    }
+
+    /// Sets up a cold-signing pair: an offline signing wallet and a view-only
+    /// watch wallet generated from its address and view key, funds them with
+    /// `funding` from the primary account and syncs outputs and key images
+    /// between them. Requires `init()` to have been called, the miner wallet
+    /// is re-opened before returning.
+    ///
+    /// Both wallets live in the same monero-wallet-rpc, the signing wallet is
+    /// offline only in the sense that it is never used to create or submit
+    /// transactions.
+    pub async fn init_cold_signing(&self, funding: u64) -> Result<ColdSigning> {
+        let wallet = self.wallet_rpc_client();
+
+        wallet.create_wallet(SIGNING_WALLET).await?;
+        let address = wallet
+            .get_address(ACCOUNT_INDEX_PRIMARY, None)
+            .await?
+            .address;
+        let view_key = wallet.query_key(KeyType::ViewKey).await?;
+
+        wallet
+            .generate_from_keys(WATCH_WALLET, &address, None, &view_key, 0)
+            .await?;
+
+        wallet.open_wallet(MINER_WALLET).await?;
+        let miner = self.get_address_primary().await?.address;
+        self.fund_account(&address, &miner, funding).await?;
+
+        wallet.open_wallet(WATCH_WALLET).await?;
+        self.sync_wallet().await?;
+        let outputs = wallet.export_outputs(true).await?;
+
+        wallet.open_wallet(SIGNING_WALLET).await?;
+        wallet.import_outputs(&outputs).await?;
+        let key_images = wallet.export_key_images(true).await?;
+
+        wallet.open_wallet(WATCH_WALLET).await?;
+        wallet
+            .import_key_images(key_images.offset, key_images.signed_key_images)
+            .await?;
+
+        wallet.open_wallet(MINER_WALLET).await?;
+
+        Ok(ColdSigning {
+            address,
+            watch_wallet: WATCH_WALLET.to_owned(),
+            signing_wallet: SIGNING_WALLET.to_owned(),
+        })
+    }
+
+    /// Creates the wallet file for wallet `name` in its own monero-wallet-rpc
+    /// and funds it with `funding` from the primary account, if `funding` is
+    /// > 0. Requires `init()` to have been called.
+    pub async fn init_wallet(&self, name: &str, funding: u64) -> Result<()> {
+        let wallet = self.wallet(name)?;
+
+        wallet.create_wallet(name).await?;
+
+        if funding > 0 {
+            let address = wallet
+                .get_address(ACCOUNT_INDEX_PRIMARY, None)
+                .await?
+                .address;
+            let miner = self.get_address_primary().await?.address;
+            self.fund_account(&address, &miner, funding).await?;
+
+            wallet.refresh(None).await?;
+
+            let balance = wallet.get_balance(ACCOUNT_INDEX_PRIMARY).await?;
+            debug_assert!(balance == funding);
+        }
+
+        Ok(())
+    }
+
     async fn fund_account(&self, address: &str, miner: &str, funding: u64) -> Result<()> {
         let monerod = self.monerod_rpc_client();
 
         self.transfer_from_primary(funding, address).await?;
-        let res = monerod.generate_blocks(10, miner).await?;
-        self.wait_for_wallet_block_height(res.height).await?;
+        let _ = monerod.generate_blocks(10, miner).await?;
+        self.sync_wallet().await?;
 
         Ok(())
     }
 
-    // It takes a little while for the wallet to sync with monerod.
-    async fn wait_for_wallet_block_height(&self, height: u32) -> Result<()> {
+    /// Explicitly refresh the open wallet so it syncs with monerod, much
+    /// quicker than waiting for the wallet to auto-refresh. Blocks monerod
+    /// has when this is called are synced when it returns.
+    pub async fn sync_wallet(&self) -> Result<Refreshed> {
         let wallet = self.wallet_rpc_client();
-        while wallet.block_height().await?.height < height {
-            time::delay_for(Duration::from_millis(WAIT_WALLET_SYNC_MILLIS)).await;
-        }
+        wallet.refresh(None).await
+    }
+
+    /// Simulates a chain reorganization on the single node by popping `depth`
+    /// blocks and mining `depth + 1` blocks in their place, then syncs the
+    /// wallet. Transactions in the popped blocks return to the tx pool and
+    /// are mined again, use `network::Network::reorg()` to orphan them.
+    pub async fn reorg(&self, depth: u32) -> Result<()> {
+        let monerod = self.monerod_rpc_client();
+        let miner = self.get_address_primary().await?.address;
+
+        monerod.pop_blocks(depth).await?;
+        let _ = monerod.generate_blocks(depth + 1, &miner).await?;
+        self.sync_wallet().await?;
+
         Ok(())
     }
 
     /// Get addresses for the primary account.
     pub async fn get_address_primary(&self) -> Result<GetAddress> {
         let wallet = self.wallet_rpc_client();
-        wallet.get_address(ACCOUNT_INDEX_PRIMARY).await
+        wallet.get_address(ACCOUNT_INDEX_PRIMARY, None).await
     }
 
     /// Get addresses for the Alice's account.
     pub async fn get_address_alice(&self) -> Result<GetAddress> {
         let wallet = self.wallet_rpc_client();
-        wallet.get_address(ACCOUNT_INDEX_ALICE).await
+        wallet.get_address(ACCOUNT_INDEX_ALICE, None).await
     }
 
     /// Get addresses for the Bob's account.
     pub async fn get_address_bob(&self) -> Result<GetAddress> {
         let wallet = self.wallet_rpc_client();
-        wallet.get_address(ACCOUNT_INDEX_BOB).await
+        wallet.get_address(ACCOUNT_INDEX_BOB, None).await
     }
 
     /// Gets the balance of the wallet primary account.
@@ -218,6 +562,113 @@ impl<'c> Monero<'c> {
         let wallet = self.wallet_rpc_client();
         wallet.transfer(ACCOUNT_INDEX_BOB, amount, address).await
     }
+
+    /// Gets the unspent outputs of account by index.
+    pub async fn get_outputs(&self, account_index: u32) -> Result<Vec<IncomingTransfer>> {
+        let wallet = self.wallet_rpc_client();
+        wallet
+            .incoming_transfers(TransferType::Available, account_index)
+            .await
+    }
+
+    /// Computes the maximum amount that can be sent from account by index in
+    /// a single transaction at `priority`, i.e. the sum of its unlocked
+    /// unspent outputs less the fee the wallet will charge.
+    pub async fn get_send_max(&self, account_index: u32, priority: Priority) -> Result<SendMax> {
+        let wallet = self.wallet_rpc_client();
+        let monerod = self.monerod_rpc_client();
+
+        let outputs: Vec<_> = self
+            .get_outputs(account_index)
+            .await?
+            .into_iter()
+            .filter(|output| output.unlocked)
+            .collect();
+        if outputs.is_empty() {
+            bail!("no unlocked outputs in account {}", account_index);
+        }
+        let total: u64 = outputs.iter().map(|output| output.amount).sum();
+        let n_inputs = u32::try_from(outputs.len())?;
+
+        // The wallet always creates at least two outputs, sending the max
+        // amount gives a dummy change output.
+        let estimate = wallet
+            .estimate_tx_size_and_weight(n_inputs, 2, RING_SIZE)
+            .await?;
+        let fee_estimate = monerod.get_fee_estimate().await?;
+
+        let fee = fee(
+            estimate.weight,
+            fee_estimate.fee,
+            fee_multiplier(priority),
+            fee_estimate.quantization_mask,
+        );
+        if fee >= total {
+            bail!("account {} cannot cover the fee {}", account_index, fee);
+        }
+
+        Ok(SendMax {
+            amount: total - fee,
+            fee,
+            weight: estimate.weight,
+        })
+    }
+
+    /// Transfers exactly the outputs with `key_images` from `account_index` to
+    /// `address`, one transaction per output so no other outputs get spent.
+    /// Fails before sending anything if an output is unknown, spent, frozen,
+    /// locked or listed twice.
+    pub async fn transfer_outputs(
+        &self,
+        account_index: u32,
+        key_images: &[String],
+        address: &str,
+    ) -> Result<Vec<Transfer>> {
+        let wallet = self.wallet_rpc_client();
+        let outputs = self.get_outputs(account_index).await?;
+
+        check_spendable(&outputs, key_images, account_index)?;
+
+        let mut transfers = Vec::new();
+        for key_image in key_images {
+            transfers.push(wallet.sweep_single(key_image, address).await?);
+        }
+
+        Ok(transfers)
+    }
+}
+
+/// Checks that every output in `key_images` is among the unspent `outputs` of
+/// `account_index` and can be swept right away.
+fn check_spendable(
+    outputs: &[IncomingTransfer],
+    key_images: &[String],
+    account_index: u32,
+) -> Result<()> {
+    for (i, key_image) in key_images.iter().enumerate() {
+        if key_images[..i].contains(key_image) {
+            bail!("key image {} is listed twice", key_image);
+        }
+
+        let output = outputs
+            .iter()
+            .find(|output| &output.key_image == key_image)
+            .ok_or_else(|| {
+                anyhow!(
+                    "no unspent output with key image {} in account {}",
+                    key_image,
+                    account_index
+                )
+            })?;
+        if output.frozen {
+            bail!("output with key image {} is frozen", key_image);
+        }
+        if !output.unlocked {
+            bail!("output with key image {} is locked", key_image);
+        }
+    }
+
+    Ok(())
 }
 
 /// Mine a block ever BLOCK_TIME_SECS seconds.
@@ -228,9 +679,72 @@ async fn mine(monerod: monerod::Client, reward_address: String) -> Result<()> {
     }
 }
 
+/// Fee multiplier monero-wallet-rpc v0.16 applies for `priority`, the
+/// default priority is lowered to unimportant since the harness tx pool is
+/// never backlogged.
+fn fee_multiplier(priority: Priority) -> u64 {
+    match priority {
+        Priority::Default | Priority::Unimportant => 1,
+        Priority::Normal => 5,
+        Priority::Elevated => 25,
+        Priority::Priority => 1000,
+    }
+}
+
+/// Fee for a transaction of `weight`, rounded up to the quantization mask like
+/// the wallet does.
+fn fee(weight: u64, fee_per_byte: u64, multiplier: u64, quantization_mask: u64) -> u64 {
+    // A mask of 0 means no rounding, like 1.
+    let quantization_mask = quantization_mask.max(1);
+    let fee = weight * fee_per_byte * multiplier;
+    (fee + quantization_mask - 1) / quantization_mask * quantization_mask
+}
+
+/// Returns all multisig infos except the one belonging to participant `index`.
+fn others(infos: &[String], index: usize) -> Vec<String> {
+    infos
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, info)| info.clone())
+        .collect()
+}
+
+/// A funded multisig wallet created by `Monero::init_multisig()`.
+#[derive(Clone, Debug)]
+pub struct Multisig {
+    /// The shared multisig address.
+    pub address: String,
+    /// Wallet file names, one per participant, open with
+    /// `wallet::Client::open_wallet()`.
+    pub wallets: Vec<String>,
+    pub threshold: u32,
+}
+
+/// A funded cold-signing wallet pair created by `Monero::init_cold_signing()`.
+#[derive(Clone, Debug)]
+pub struct ColdSigning {
+    /// The address shared by both wallets.
+    pub address: String,
+    /// View-only wallet file, creates unsigned transactions and submits them.
+    pub watch_wallet: String,
+    /// Wallet file with the spend key, signs transactions.
+    pub signing_wallet: String,
+}
+
+/// The maximum amount that can be sent from an account, see
+/// `Monero::get_send_max()`.
+#[derive(Clone, Copy, Debug)]
+pub struct SendMax {
+    pub amount: u64,
+    pub fee: u64,
+    /// Estimated transaction weight the fee is computed from.
+    pub weight: u64,
+}
+
 // We should be able to use monero-rs for this but it does not include all
 // the fields.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BlockHeader {
     pub block_size: u32,
     pub depth: u32,
@@ -246,3 +760,51 @@ pub struct BlockHeader {
     pub reward: u64,
     pub timestamp: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn fee_is_rounded_up_to_quantization_mask() {
+        assert_that!(fee(1500, 20, 1, 10_000)).is_equal_to(30_000);
+        assert_that!(fee(1501, 20, 1, 10_000)).is_equal_to(40_000);
+    }
+
+    #[test]
+    fn fee_is_not_rounded_without_quantization_mask() {
+        assert_that!(fee(1501, 20, 1, 0)).is_equal_to(30_020);
+    }
+
+    fn output(key_image: &str, frozen: bool, unlocked: bool) -> IncomingTransfer {
+        IncomingTransfer {
+            amount: 1,
+            global_index: 0,
+            key_image: key_image.to_owned(),
+            spent: false,
+            subaddr_index: wallet::SubAddressIndex { major: 0, minor: 0 },
+            tx_hash: String::new(),
+            frozen,
+            unlocked,
+        }
+    }
+
+    #[test]
+    fn checks_every_key_image_before_sweeping() {
+        let outputs = vec![
+            output("a", false, true),
+            output("frozen", true, true),
+            output("locked", false, false),
+        ];
+        let key_images = |images: &[&str]| -> Vec<String> {
+            images.iter().map(|image| (*image).to_owned()).collect()
+        };
+
+        assert_that!(check_spendable(&outputs, &key_images(&["a"]), 0)).is_ok();
+        assert_that!(check_spendable(&outputs, &key_images(&["a", "unknown"]), 0)).is_err();
+        assert_that!(check_spendable(&outputs, &key_images(&["a", "frozen"]), 0)).is_err();
+        assert_that!(check_spendable(&outputs, &key_images(&["a", "locked"]), 0)).is_err();
+        assert_that!(check_spendable(&outputs, &key_images(&["a", "a"]), 0)).is_err();
+    }
+}
@@ -0,0 +1,272 @@
+//! In-process fakes of monerod and monero-wallet-rpc for tests that cannot
+//! run Docker.
+//!
+//! The fakes implement the subset of the RPC interface used by the clients in
+//! `rpc`, point a client at one with e.g.
+//! `monerod::Client::localhost(mock.port)`, or run the `Monero` helpers
+//! against them with `Monero::from_backend()` and a `Backend`.
+pub mod monerod;
+pub mod wallet;
+
+use anyhow::Result;
+use futures::stream;
+use hyper::{
+    header::{AUTHORIZATION, WWW_AUTHENTICATE},
+    server::accept,
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    convert::Infallible,
+    hash::{Hash, Hasher},
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::Arc,
+};
+use tokio_tls::TlsAcceptor;
+
+use crate::{
+    backend,
+    mock::{monerod::Monerod, wallet::WalletRpc},
+    rpc::{digest, Certificate, Credentials},
+    server::Running,
+};
+
+/// JSON RPC error code used for all errors, monerod and monero-wallet-rpc use
+/// a range of negative codes.
+const ERROR_CODE: i32 = -1;
+
+/// A fake monerod and fake monero-wallet-rpcs standing in for a running
+/// `backend::Backend`. All wallet-rpcs share one ledger.
+#[derive(Debug)]
+pub struct Backend {
+    pub monerod: Monerod,
+    /// Holds the miner wallet, clone it to fund wallets after handing the
+    /// backend to `Monero`, mined blocks do not credit the fake wallets.
+    pub wallet_rpc: WalletRpc,
+    /// Additional wallet-rpcs by wallet name.
+    pub wallets: HashMap<String, WalletRpc>,
+}
+
+impl Backend {
+    /// Starts the fakes with an additional wallet-rpc for each name in
+    /// `wallets`.
+    pub fn start(wallets: &[&str]) -> Result<Self> {
+        let monerod = Monerod::start()?;
+        let wallet_rpc = WalletRpc::start()?;
+
+        let mut others = HashMap::new();
+        for name in wallets {
+            others.insert((*name).to_owned(), wallet_rpc.start_sibling()?);
+        }
+
+        Ok(Self {
+            monerod,
+            wallet_rpc,
+            wallets: others,
+        })
+    }
+}
+
+impl backend::Backend for Backend {
+    fn monerod_rpc_port(&self) -> u16 {
+        self.monerod.port
+    }
+
+    fn wallet_rpc_port(&self) -> u16 {
+        self.wallet_rpc.port
+    }
+
+    fn wallet_rpc_ports(&self) -> HashMap<String, u16> {
+        self.wallets
+            .iter()
+            .map(|(name, wallet_rpc)| (name.clone(), wallet_rpc.port))
+            .collect()
+    }
+
+    fn wallet_rpc_login(&self) -> Option<Credentials> {
+        None
+    }
+
+    fn rpc_certificate(&self) -> Option<Certificate> {
+        None
+    }
+}
+
+/// Request handling of a fake daemon.
+trait Handler: Send + Sync + 'static {
+    /// Handles JSON RPC `method`, returns the result or an error message.
+    fn json_rpc(&self, method: &str, params: Value) -> Result<Value, String>;
+
+    /// Handles a request to an endpoint other than `/json_rpc`, returns none
+    /// if there is no such endpoint.
+    fn endpoint(&self, path: &str, params: Value) -> Option<Value>;
+}
+
+/// Starts an HTTP server on a random localhost port passing every request to
+/// `handler`. With `login` requests must be authenticated with HTTP digest
+/// authentication, like a daemon started with `--rpc-login`. With
+/// `certificate` the server speaks HTTPS, like a daemon started with
+/// `--rpc-ssl`.
+fn serve<H: Handler>(
+    handler: Arc<H>,
+    login: Option<Credentials>,
+    certificate: Option<&Certificate>,
+) -> Result<Running> {
+    let login = Arc::new(login.map(|credentials| Login {
+        credentials,
+        nonce: format!("{:032x}", rand::thread_rng().gen::<u128>()),
+    }));
+
+    // Called for each connection, plain TCP or TLS.
+    let connection = move || {
+        let handler = handler.clone();
+        let login = login.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let handler = handler.clone();
+                let login = login.clone();
+                async move { handle(&*handler, login.as_ref().as_ref(), request).await }
+            }))
+        }
+    };
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+
+    match certificate {
+        None => {
+            let make_service = make_service_fn(move |_| connection());
+            let server = Server::try_bind(&addr)?.serve(make_service);
+            Ok(Running::spawn(server.local_addr().port(), server))
+        }
+        Some(certificate) => {
+            let identity = native_tls::Identity::from_pkcs8(
+                certificate.certificate.as_bytes(),
+                certificate.private_key.as_bytes(),
+            )?;
+            let acceptor = TlsAcceptor::from(native_tls::TlsAcceptor::new(identity)?);
+
+            let listener = TcpListener::bind(addr)?;
+            let port = listener.local_addr()?.port();
+            listener.set_nonblocking(true)?;
+            let listener = tokio::net::TcpListener::from_std(listener)?;
+
+            // Connections failing the handshake are dropped, an error would
+            // stop the server.
+            let connections = stream::unfold(
+                (listener, acceptor),
+                |(mut listener, acceptor)| async move {
+                    loop {
+                        let tcp = match listener.accept().await {
+                            Ok((tcp, _)) => tcp,
+                            Err(e) => return Some((Err(e), (listener, acceptor))),
+                        };
+                        if let Ok(tls) = acceptor.accept(tcp).await {
+                            return Some((Ok::<_, io::Error>(tls), (listener, acceptor)));
+                        }
+                    }
+                },
+            );
+
+            let make_service = make_service_fn(move |_| connection());
+            let server = Server::builder(accept::from_stream(connections)).serve(make_service);
+            Ok(Running::spawn(port, server))
+        }
+    }
+}
+
+/// Credentials of a fake and the nonce it challenges clients with.
+#[derive(Debug)]
+struct Login {
+    credentials: Credentials,
+    nonce: String,
+}
+
+async fn handle<H: Handler>(
+    handler: &H,
+    login: Option<&Login>,
+    request: Request<Body>,
+) -> Result<Response<Body>> {
+    if let Some(login) = login {
+        let header = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|header| header.to_str().ok());
+        let authorized = match header {
+            Some(header) => digest::verify(
+                &login.credentials,
+                request.method().as_str(),
+                &login.nonce,
+                header,
+            ),
+            None => false,
+        };
+
+        if !authorized {
+            return Ok(Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .header(WWW_AUTHENTICATE, digest::challenge_header(&login.nonce))
+                .body(Body::empty())?);
+        }
+    }
+
+    let path = request.uri().path().trim_start_matches('/').to_owned();
+    let body = hyper::body::to_bytes(request.into_body()).await?;
+    // Requests without parameters have an empty body.
+    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let response = if path == "json_rpc" {
+        let id = body["id"].clone();
+        let method = body["method"].as_str().unwrap_or_default().to_owned();
+        let params = body["params"].clone();
+
+        match handler.json_rpc(&method, params) {
+            Ok(result) => json!({ "id": id, "jsonrpc": "2.0", "result": result }),
+            Err(message) => json!({
+                "id": id,
+                "jsonrpc": "2.0",
+                "error": { "code": ERROR_CODE, "message": message },
+            }),
+        }
+    } else {
+        match handler.endpoint(&path, body) {
+            Some(response) => response,
+            None => {
+                return Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())?)
+            }
+        }
+    };
+
+    Ok(Response::builder()
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&response)?))?)
+}
+
+/// Deserializes request parameters, treating missing parameters as empty.
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, String> {
+    let params = if params.is_null() || params == Value::String(String::new()) {
+        json!({})
+    } else {
+        params
+    };
+
+    serde_json::from_value(params).map_err(|e| format!("invalid params: {}", e))
+}
+
+/// Deterministic, 32 byte hex encoded stand-in for a hash of `data`.
+fn fake_hash<T: Hash>(data: T) -> String {
+    (0..4u8)
+        .map(|salt| {
+            let mut hasher = DefaultHasher::new();
+            salt.hash(&mut hasher);
+            data.hash(&mut hasher);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
@@ -0,0 +1,379 @@
+//! Fake monerod with a simulated regtest chain and tx pool.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    mock::{fake_hash, serve, Handler},
+    rpc::{monerod, Certificate, Credentials, Tls},
+    server::Running,
+    BlockHeader,
+};
+
+/// Reward of every block, the fake chain has no emission curve.
+pub const BLOCK_REWARD: u64 = 17_592_186_044_415;
+
+/// Per byte fee returned by `get_fee_estimate`.
+pub const FEE_PER_BYTE: u64 = 20_000;
+
+/// Fee quantization mask returned by `get_fee_estimate`.
+pub const FEE_QUANTIZATION_MASK: u64 = 10_000;
+
+/// Timestamp of the genesis block, blocks are two minutes apart.
+const GENESIS_TIMESTAMP: u32 = 1_397_818_193;
+const BLOCK_TIME_SECS: u32 = 120;
+
+/// A running fake monerod, stops when dropped.
+#[derive(Debug)]
+pub struct Monerod {
+    /// Local port the fake listens on.
+    pub port: u16,
+    chain: Arc<Chain>,
+    login: Option<Credentials>,
+    certificate: Option<Certificate>,
+    _server: Running,
+}
+
+impl Monerod {
+    /// Starts a fake monerod on a random localhost port with a chain holding
+    /// only the genesis block.
+    pub fn start() -> Result<Self> {
+        Self::start_with_login(None)
+    }
+
+    /// Starts the fake requiring HTTP digest authentication with `login`,
+    /// like a daemon started with `--rpc-login`, if some.
+    pub fn start_with_login(login: Option<Credentials>) -> Result<Self> {
+        Self::start_with(login, None)
+    }
+
+    /// Starts the fake serving HTTPS with `certificate`, like a daemon
+    /// started with `--rpc-ssl`.
+    pub fn start_with_tls(certificate: Certificate) -> Result<Self> {
+        Self::start_with(None, Some(certificate))
+    }
+
+    fn start_with(login: Option<Credentials>, certificate: Option<Certificate>) -> Result<Self> {
+        let chain = Arc::new(Chain::default());
+        let server = serve(chain.clone(), login.clone(), certificate.as_ref())?;
+
+        Ok(Self {
+            port: server.port,
+            chain,
+            login,
+            certificate,
+            _server: server,
+        })
+    }
+
+    /// Client for the fake, with the fake's credentials if it requires
+    /// login, trusting the fake's certificate if it serves HTTPS.
+    pub fn client(&self) -> monerod::Client {
+        let client = match &self.certificate {
+            Some(certificate) => {
+                let tls = Tls::CaCertificate(certificate.certificate.clone());
+                monerod::Client::localhost_with_tls(self.port, tls)
+                    .expect("certificate is valid PEM")
+            }
+            None => monerod::Client::localhost(self.port),
+        };
+        match &self.login {
+            Some(login) => client.with_credentials(login.clone()),
+            None => client,
+        }
+    }
+
+    /// Number of blocks in the chain.
+    pub fn height(&self) -> u32 {
+        self.chain.state().height()
+    }
+
+    /// Transaction hashes in the tx pool.
+    pub fn tx_pool(&self) -> Vec<String> {
+        self.chain
+            .state()
+            .pool
+            .iter()
+            .map(|tx| tx.hash.clone())
+            .collect()
+    }
+
+    /// Mining reward address of every block, by height.
+    pub fn reward_addresses(&self) -> Vec<String> {
+        self.chain
+            .state()
+            .blocks
+            .iter()
+            .map(|block| block.reward_address.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct Chain {
+    state: Mutex<State>,
+}
+
+#[derive(Debug)]
+struct State {
+    blocks: Vec<Block>,
+    pool: Vec<Tx>,
+}
+
+#[derive(Clone, Debug)]
+struct Block {
+    header: BlockHeader,
+    reward_address: String,
+    txs: Vec<Tx>,
+}
+
+#[derive(Clone, Debug)]
+struct Tx {
+    hash: String,
+    blob: String,
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        let mut state = State {
+            blocks: vec![],
+            pool: vec![],
+        };
+        state.push_block("");
+
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+}
+
+impl Chain {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("poisoned lock")
+    }
+}
+
+impl State {
+    fn height(&self) -> u32 {
+        u32::try_from(self.blocks.len()).expect("chain fits in u32")
+    }
+
+    /// Mines a block to `reward_address` including the whole tx pool.
+    fn push_block(&mut self, reward_address: &str) -> String {
+        let height = self.height();
+        let prev_hash = self
+            .blocks
+            .last()
+            .map(|block| block.header.hash.clone())
+            .unwrap_or_else(|| "0".repeat(64));
+        let txs: Vec<Tx> = self.pool.drain(..).collect();
+        let tx_hashes: Vec<&str> = txs.iter().map(|tx| tx.hash.as_str()).collect();
+        let hash = fake_hash((height, &prev_hash, reward_address, &tx_hashes));
+
+        self.blocks.push(Block {
+            header: BlockHeader {
+                block_size: 0,
+                depth: 0,
+                difficulty: 1,
+                hash: hash.clone(),
+                height,
+                major_version: 12,
+                minor_version: 12,
+                nonce: 0,
+                num_txes: u32::try_from(txs.len()).expect("block fits in u32"),
+                orphan_status: false,
+                prev_hash,
+                reward: BLOCK_REWARD,
+                timestamp: GENESIS_TIMESTAMP + height * BLOCK_TIME_SECS,
+            },
+            reward_address: reward_address.to_owned(),
+            txs,
+        });
+
+        hash
+    }
+
+    fn header(&self, height: u32) -> Option<BlockHeader> {
+        let top = self.height() - 1;
+        self.blocks.get(height as usize).map(|block| BlockHeader {
+            depth: top - height,
+            ..block.header.clone()
+        })
+    }
+
+    fn find_tx(&self, hash: &str) -> Option<Value> {
+        let in_pool = self
+            .pool
+            .iter()
+            .find(|tx| tx.hash == hash)
+            .map(|tx| (tx, 0));
+        let in_block = || {
+            self.blocks.iter().find_map(|block| {
+                block
+                    .txs
+                    .iter()
+                    .find(|tx| tx.hash == hash)
+                    .map(|tx| (tx, block.header.height))
+            })
+        };
+
+        in_pool.or_else(in_block).map(|(tx, block_height)| {
+            json!({
+                "tx_hash": tx.hash,
+                "as_hex": tx.blob,
+                "in_pool": block_height == 0,
+                "block_height": block_height,
+                "double_spend_seen": false,
+            })
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct GenerateBlocksParams {
+    amount_of_blocks: u32,
+    wallet_address: String,
+}
+
+#[derive(Deserialize)]
+struct HeightParams {
+    height: u32,
+}
+
+#[derive(Deserialize)]
+struct PopBlocksParams {
+    nblocks: u32,
+}
+
+#[derive(Deserialize)]
+struct SendRawTransactionParams {
+    tx_as_hex: String,
+}
+
+#[derive(Deserialize)]
+struct GetTransactionsParams {
+    txs_hashes: Vec<String>,
+}
+
+impl Handler for Chain {
+    fn json_rpc(&self, method: &str, params: Value) -> Result<Value, String> {
+        let mut state = self.state();
+
+        match method {
+            "generateblocks" => {
+                let p: GenerateBlocksParams = super::params(params)?;
+                if p.wallet_address.is_empty() {
+                    return Err("Failed to parse wallet address".to_owned());
+                }
+                let blocks: Vec<String> = (0..p.amount_of_blocks)
+                    .map(|_| state.push_block(&p.wallet_address))
+                    .collect();
+
+                // Like monerod, the height of the last block, not the number of
+                // blocks.
+                let height = state.height() - 1;
+
+                Ok(json!({ "blocks": blocks, "height": height, "status": "OK" }))
+            }
+            "get_block_header_by_height" => {
+                let p: HeightParams = super::params(params)?;
+                let header = state.header(p.height).ok_or_else(|| {
+                    format!(
+                        "Requested block height: {} greater than current top block height: {}",
+                        p.height,
+                        state.height() - 1
+                    )
+                })?;
+
+                Ok(json!({ "block_header": header, "status": "OK", "untrusted": false }))
+            }
+            "get_info" => Ok(json!({
+                "height": state.height(),
+                "target_height": 0,
+                "top_block_hash": state.blocks.last().map(|block| block.header.hash.clone()),
+                "incoming_connections_count": 0,
+                "outgoing_connections_count": 0,
+                "tx_pool_size": state.pool.len(),
+                "offline": true,
+                "status": "OK",
+            })),
+            "get_connections" => Ok(json!({ "connections": [], "status": "OK" })),
+            "get_fee_estimate" => Ok(json!({
+                "fee": FEE_PER_BYTE,
+                "quantization_mask": FEE_QUANTIZATION_MASK,
+                "status": "OK",
+                "untrusted": false,
+            })),
+            "set_bans" => Ok(json!({ "status": "OK" })),
+            _ => Err("Method not found".to_owned()),
+        }
+    }
+
+    fn endpoint(&self, path: &str, params: Value) -> Option<Value> {
+        let mut state = self.state();
+
+        let response = match path {
+            "pop_blocks" => match super::params::<PopBlocksParams>(params) {
+                Ok(p) if p.nblocks < state.height() => {
+                    for _ in 0..p.nblocks {
+                        let block = state.blocks.pop().expect("more blocks than popped");
+                        state.pool.extend(block.txs);
+                    }
+                    json!({ "height": state.height(), "status": "OK" })
+                }
+                _ => json!({ "height": state.height(), "status": "Failed" }),
+            },
+            "send_raw_transaction" => match super::params::<SendRawTransactionParams>(params) {
+                Ok(p) if is_hex(&p.tx_as_hex) => {
+                    let hash = fake_hash(&p.tx_as_hex);
+                    let known = state.find_tx(&hash).is_some();
+                    if !known {
+                        state.pool.push(Tx {
+                            hash,
+                            blob: p.tx_as_hex,
+                        });
+                    }
+                    json!({
+                        "status": "OK",
+                        "reason": "",
+                        "double_spend": false,
+                        "not_relayed": false,
+                    })
+                }
+                _ => json!({
+                    "status": "Failed",
+                    "reason": "Failed to parse tx",
+                    "double_spend": false,
+                    "not_relayed": true,
+                }),
+            },
+            "get_transactions" => match super::params::<GetTransactionsParams>(params) {
+                Ok(p) => {
+                    let (txs, missed_tx): (Vec<_>, Vec<_>) = p
+                        .txs_hashes
+                        .iter()
+                        .map(|hash| (hash, state.find_tx(hash)))
+                        .partition(|(_, tx)| tx.is_some());
+                    let txs: Vec<Value> = txs.into_iter().filter_map(|(_, tx)| tx).collect();
+                    let missed_tx: Vec<&String> =
+                        missed_tx.into_iter().map(|(hash, _)| hash).collect();
+
+                    json!({ "txs": txs, "missed_tx": missed_tx, "status": "OK" })
+                }
+                Err(_) => json!({ "status": "Failed" }),
+            },
+            _ => return None,
+        };
+
+        Some(response)
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit())
+}
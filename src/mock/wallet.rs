@@ -0,0 +1,490 @@
+//! Fake monero-wallet-rpc with a deterministic in-memory ledger.
+//!
+//! Every wallet created through the fake, or through fakes started with
+//! `WalletRpc::start_sibling()`, shares one ledger, so transfers to the
+//! address of another wallet or account of the ledger are credited to it.
+//! Transfers are confirmed immediately and funds never lock.
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    mock::{fake_hash, serve, Handler},
+    rpc::{wallet, Certificate, Credentials, Tls},
+    server::Running,
+};
+
+/// Fee charged for every transfer, independent of priority and size.
+pub const TRANSFER_FEE: u64 = 30_000_000;
+
+/// A running fake monero-wallet-rpc, stops when the last clone is dropped.
+#[derive(Clone, Debug)]
+pub struct WalletRpc {
+    /// Local port the fake listens on.
+    pub port: u16,
+    ledger: Arc<Ledger>,
+    login: Option<Credentials>,
+    certificate: Option<Certificate>,
+    _server: Arc<Running>,
+}
+
+impl WalletRpc {
+    /// Starts a fake monero-wallet-rpc on a random localhost port with no
+    /// wallets, at block height 1.
+    pub fn start() -> Result<Self> {
+        Self::start_with_login(None)
+    }
+
+    /// Starts the fake requiring HTTP digest authentication with `login`,
+    /// like a daemon started with `--rpc-login`, if some.
+    pub fn start_with_login(login: Option<Credentials>) -> Result<Self> {
+        Self::start_with(login, None)
+    }
+
+    /// Starts the fake serving HTTPS with `certificate`, like a daemon
+    /// started with `--rpc-ssl`.
+    pub fn start_with_tls(certificate: Certificate) -> Result<Self> {
+        Self::start_with(None, Some(certificate))
+    }
+
+    /// Starts another fake on a random localhost port sharing this fake's
+    /// ledger, login and certificate, like a second monero-wallet-rpc
+    /// connected to the same monerod. Each fake has its own open wallet.
+    pub fn start_sibling(&self) -> Result<Self> {
+        Self::start_with_ledger(
+            self.ledger.clone(),
+            self.login.clone(),
+            self.certificate.clone(),
+        )
+    }
+
+    fn start_with(login: Option<Credentials>, certificate: Option<Certificate>) -> Result<Self> {
+        Self::start_with_ledger(Arc::new(Ledger::default()), login, certificate)
+    }
+
+    fn start_with_ledger(
+        ledger: Arc<Ledger>,
+        login: Option<Credentials>,
+        certificate: Option<Certificate>,
+    ) -> Result<Self> {
+        let instance = Arc::new(Instance {
+            ledger: ledger.clone(),
+            open: Mutex::new(None),
+        });
+        let server = serve(instance, login.clone(), certificate.as_ref())?;
+
+        Ok(Self {
+            port: server.port,
+            ledger,
+            login,
+            certificate,
+            _server: Arc::new(server),
+        })
+    }
+
+    /// Client for the fake, with the fake's credentials if it requires
+    /// login, trusting the fake's certificate if it serves HTTPS.
+    pub fn client(&self) -> wallet::Client {
+        let client = match &self.certificate {
+            Some(certificate) => {
+                let tls = Tls::CaCertificate(certificate.certificate.clone());
+                wallet::Client::localhost_with_tls(self.port, tls)
+                    .expect("certificate is valid PEM")
+            }
+            None => wallet::Client::localhost(self.port),
+        };
+        match &self.login {
+            Some(login) => client.with_credentials(login.clone()),
+            None => client,
+        }
+    }
+
+    /// Credits `amount` to account by index of `wallet`, as if the account
+    /// received an unlocked output.
+    pub fn fund(&self, wallet: &str, account_index: u32, amount: u64) -> Result<()> {
+        let mut state = self.ledger.state();
+        let account = match state
+            .wallets
+            .get_mut(wallet)
+            .and_then(|accounts| accounts.get_mut(account_index as usize))
+        {
+            Some(account) => account,
+            None => bail!("no account {} in wallet {}", account_index, wallet),
+        };
+        account.balance += amount;
+
+        Ok(())
+    }
+
+    /// Credits `amount` to the account with primary `address`, in any wallet
+    /// of the ledger.
+    pub fn fund_address(&self, address: &str, amount: u64) -> Result<()> {
+        let mut state = self.ledger.state();
+        match state.find_account(address) {
+            Some(account) => account.balance += amount,
+            None => bail!("no account with address {}", address),
+        }
+
+        Ok(())
+    }
+
+    /// Sets the block height reported by `get_height`, confirmations of
+    /// transfers are counted against it.
+    pub fn set_height(&self, height: u32) {
+        self.ledger.state().height = height;
+    }
+
+    /// Makes the next call to `method` fail with `message`. Errors queue up,
+    /// each is returned once.
+    pub fn fail_next(&self, method: &str, message: &str) {
+        self.ledger
+            .state()
+            .errors
+            .entry(method.to_owned())
+            .or_default()
+            .push_back(message.to_owned());
+    }
+}
+
+#[derive(Debug)]
+struct Ledger {
+    state: Mutex<State>,
+}
+
+/// A single fake wallet-rpc serving a possibly shared ledger.
+#[derive(Debug)]
+struct Instance {
+    ledger: Arc<Ledger>,
+    /// Filename of the wallet open in this wallet-rpc.
+    open: Mutex<Option<String>>,
+}
+
+#[derive(Debug)]
+struct State {
+    height: u32,
+    /// Accounts of every wallet by filename.
+    wallets: BTreeMap<String, Vec<Account>>,
+    /// Open wallet of the wallet-rpc whose request is being handled.
+    open: Option<String>,
+    txs: Vec<Tx>,
+    errors: HashMap<String, VecDeque<String>>,
+}
+
+#[derive(Debug)]
+struct Account {
+    label: String,
+    address: String,
+    balance: u64,
+}
+
+#[derive(Debug)]
+struct Tx {
+    hash: String,
+    key: String,
+    height: u32,
+    destinations: Vec<Destination>,
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                height: 1,
+                wallets: BTreeMap::new(),
+                open: None,
+                txs: vec![],
+                errors: HashMap::new(),
+            }),
+        }
+    }
+}
+
+impl Ledger {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().expect("poisoned lock")
+    }
+}
+
+impl State {
+    fn open_wallet(&mut self) -> Result<&mut Vec<Account>, String> {
+        let wallets = &mut self.wallets;
+        self.open
+            .as_ref()
+            .and_then(move |filename| wallets.get_mut(filename))
+            .ok_or_else(|| "No wallet file".to_owned())
+    }
+
+    fn account(&mut self, account_index: u32) -> Result<&mut Account, String> {
+        self.open_wallet()?
+            .get_mut(account_index as usize)
+            .ok_or_else(|| "account index is out of bound".to_owned())
+    }
+
+    fn create_account(&mut self, label: String) -> Result<Value, String> {
+        let filename = self.open.clone().unwrap_or_default();
+        let accounts = self.open_wallet()?;
+        let account_index = accounts.len();
+        let address = fake_address(&filename, account_index);
+
+        accounts.push(Account {
+            label,
+            address: address.clone(),
+            balance: 0,
+        });
+
+        Ok(json!({ "account_index": account_index, "address": address }))
+    }
+
+    fn transfer(&mut self, p: TransferParams) -> Result<Value, String> {
+        if p.destinations.is_empty() {
+            return Err("No destinations for this transfer".to_owned());
+        }
+        if p.destinations.iter().any(|d| d.amount == 0) {
+            return Err("amount 0 in transaction".to_owned());
+        }
+        // Addresses outside the ledger are accepted, their funds vanish.
+        if p.destinations.iter().any(|d| d.address.is_empty()) {
+            return Err("Failed to parse address".to_owned());
+        }
+
+        let amount: u64 = p.destinations.iter().map(|d| d.amount).sum();
+        let account = self.account(p.account_index)?;
+        if account.balance < amount + TRANSFER_FEE {
+            return Err("not enough money".to_owned());
+        }
+        account.balance -= amount + TRANSFER_FEE;
+
+        for destination in &p.destinations {
+            if let Some(account) = self.find_account(&destination.address) {
+                account.balance += destination.amount;
+            }
+        }
+
+        let hash = fake_hash(("tx", self.txs.len()));
+        let key = fake_hash(("tx_key", self.txs.len()));
+        self.txs.push(Tx {
+            hash: hash.clone(),
+            key: key.clone(),
+            height: self.height,
+            destinations: p.destinations,
+        });
+
+        Ok(json!({
+            "amount": amount,
+            "fee": TRANSFER_FEE,
+            "multisig_txset": "",
+            "tx_blob": "",
+            "tx_hash": hash,
+            "tx_key": if p.get_tx_key { key } else { String::new() },
+            "tx_metadata": "",
+            "unsigned_txset": "",
+        }))
+    }
+
+    fn check_tx_key(&self, p: CheckTxKeyParams) -> Result<Value, String> {
+        let tx = self
+            .txs
+            .iter()
+            .find(|tx| tx.hash == p.txid)
+            .ok_or_else(|| "Failed to get transaction from daemon".to_owned())?;
+        if tx.key != p.tx_key {
+            return Err("Failed to parse tx key".to_owned());
+        }
+
+        let received: u64 = tx
+            .destinations
+            .iter()
+            .filter(|d| d.address == p.address)
+            .map(|d| d.amount)
+            .sum();
+
+        Ok(json!({
+            "confirmations": self.height.saturating_sub(tx.height),
+            "in_pool": false,
+            "received": received,
+        }))
+    }
+
+    fn find_account(&mut self, address: &str) -> Option<&mut Account> {
+        self.wallets
+            .values_mut()
+            .flat_map(|accounts| accounts.iter_mut())
+            .find(|account| account.address == address)
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateWalletParams {
+    filename: String,
+}
+
+#[derive(Deserialize)]
+struct LabelParams {
+    #[serde(default)]
+    label: String,
+}
+
+#[derive(Deserialize)]
+struct AccountParams {
+    #[serde(default)]
+    account_index: u32,
+}
+
+#[derive(Deserialize)]
+struct TransferParams {
+    #[serde(default)]
+    account_index: u32,
+    destinations: Vec<Destination>,
+    #[serde(default)]
+    get_tx_key: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Destination {
+    amount: u64,
+    address: String,
+}
+
+#[derive(Deserialize)]
+struct CheckTxKeyParams {
+    txid: String,
+    tx_key: String,
+    address: String,
+}
+
+impl Handler for Instance {
+    fn json_rpc(&self, method: &str, params: Value) -> Result<Value, String> {
+        let mut state = self.ledger.state();
+        let mut open = self.open.lock().expect("poisoned lock");
+
+        // The ledger is locked for the whole request, so the open wallet of
+        // this wallet-rpc can be swapped in and out.
+        state.open = open.take();
+        let result = state.json_rpc(method, params);
+        *open = state.open.take();
+
+        result
+    }
+
+    fn endpoint(&self, _: &str, _: Value) -> Option<Value> {
+        None
+    }
+}
+
+impl State {
+    fn json_rpc(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        if let Some(message) = self
+            .errors
+            .get_mut(method)
+            .and_then(|errors| errors.pop_front())
+        {
+            return Err(message);
+        }
+
+        match method {
+            "create_wallet" => {
+                let p: CreateWalletParams = super::params(params)?;
+                if self.wallets.contains_key(&p.filename) {
+                    return Err("Cannot create wallet. Already exists.".to_owned());
+                }
+                self.wallets.insert(p.filename.clone(), vec![]);
+                self.open = Some(p.filename);
+                self.create_account("Primary account".to_owned())?;
+
+                Ok(json!({}))
+            }
+            "open_wallet" => {
+                let p: CreateWalletParams = super::params(params)?;
+                if !self.wallets.contains_key(&p.filename) {
+                    return Err("Failed to open wallet".to_owned());
+                }
+                self.open = Some(p.filename);
+
+                Ok(json!({}))
+            }
+            "create_account" => {
+                let p: LabelParams = super::params(params)?;
+                self.create_account(p.label)
+            }
+            "get_accounts" => {
+                let accounts: Vec<Value> = self
+                    .open_wallet()?
+                    .iter()
+                    .enumerate()
+                    .map(|(index, account)| {
+                        json!({
+                            "account_index": index,
+                            "balance": account.balance,
+                            "base_address": account.address,
+                            "label": account.label,
+                            "tag": "",
+                            "unlocked_balance": account.balance,
+                        })
+                    })
+                    .collect();
+                let total: u64 = self.open_wallet()?.iter().map(|a| a.balance).sum();
+
+                Ok(json!({
+                    "subaddress_accounts": accounts,
+                    "total_balance": total,
+                    "total_unlocked_balance": total,
+                }))
+            }
+            "get_address" => {
+                let p: AccountParams = super::params(params)?;
+                let account = self.account(p.account_index)?;
+
+                Ok(json!({
+                    "address": account.address,
+                    "addresses": [{
+                        "address": account.address,
+                        "address_index": 0,
+                        "label": account.label,
+                        "used": account.balance > 0,
+                    }],
+                }))
+            }
+            "get_balance" => {
+                let p: AccountParams = super::params(params)?;
+                let balance = self.account(p.account_index)?.balance;
+
+                Ok(json!({
+                    "balance": balance,
+                    "blocks_to_unlock": 0,
+                    "multisig_import_needed": false,
+                    "time_to_unlock": 0,
+                    "unlocked_balance": balance,
+                }))
+            }
+            "transfer" => {
+                let p: TransferParams = super::params(params)?;
+                self.transfer(p)
+            }
+            "get_height" => {
+                self.open_wallet()?;
+                Ok(json!({ "height": self.height }))
+            }
+            "check_tx_key" => {
+                let p: CheckTxKeyParams = super::params(params)?;
+                self.check_tx_key(p)
+            }
+            "refresh" => {
+                self.open_wallet()?;
+                // Transfers are credited immediately, there is nothing to
+                // fetch.
+                Ok(json!({ "blocks_fetched": 0, "received_money": false }))
+            }
+            _ => Err("Method not found".to_owned()),
+        }
+    }
+}
+
+/// Deterministic stand-in for the primary address of an account.
+fn fake_address(filename: &str, account_index: usize) -> String {
+    format!("4{}", fake_hash((filename, account_index)))
+}
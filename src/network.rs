@@ -0,0 +1,415 @@
+//! A private regtest network of several monerod nodes, each in its own
+//! container and each with its own monero-wallet-rpc, peering exclusively
+//! with each other over a docker network.
+
+use anyhow::{bail, Result};
+use rand::Rng;
+use std::{
+    convert::TryFrom,
+    process::Command,
+    time::{Duration, Instant},
+};
+use testcontainers::{
+    clients::Cli,
+    core::{Port, RunArgs},
+    Container, Docker,
+};
+use tokio::time;
+
+use crate::{
+    image::{self, MONEROD_P2P_PORT, MONEROD_RPC_PORT, WALLET_RPC_PORT},
+    rpc::{
+        monerod::{self, Connection, GenerateBlocks},
+        wallet::{self, Transfer},
+    },
+};
+
+/// Poll interval when waiting for nodes to connect to each other.
+const WAIT_PEERS_MILLIS: u64 = 1000;
+
+/// How long to wait for nodes to connect, disconnect or sync before giving
+/// up.
+const WAIT_TIMEOUT_SECS: u64 = 120;
+
+/// Bans used to partition nodes are lifted explicitly, make them outlast any
+/// test.
+const PARTITION_BAN_SECS: u32 = 24 * 60 * 60;
+
+#[derive(Debug)]
+pub struct Network<'c> {
+    /// Name of the docker network the nodes are connected to.
+    pub name: String,
+    pub nodes: Vec<Node<'c>>,
+}
+
+#[derive(Debug)]
+pub struct Node<'c> {
+    /// Container name, also the node's host name on the docker network.
+    pub name: String,
+    pub docker: Container<'c, Cli, image::Monero>,
+    pub monerod_rpc_port: u16,
+    pub wallet_rpc_port: u16,
+}
+
+/// Height and peers of a node, see `Network::status()`.
+#[derive(Clone, Debug)]
+pub struct NodeStatus {
+    pub name: String,
+    /// Number of blocks on the node's chain, one more than the height of its
+    /// top block.
+    pub height: u32,
+    pub connections: Vec<Connection>,
+}
+
+/// Outcome of `Network::reorg()`.
+#[derive(Clone, Debug)]
+pub struct Reorg {
+    /// Node whose chain won.
+    pub winner: usize,
+    /// Number of blocks the losing node rolled back.
+    pub depth: u32,
+    /// Height of both nodes after the reorg, like `NodeStatus::height`.
+    pub height: u32,
+    /// Hashes of the blocks that were reorged out.
+    pub orphaned: Vec<String>,
+}
+
+/// Conflicting transactions created by `Network::double_spend()`.
+#[derive(Clone, Debug)]
+pub struct DoubleSpend {
+    /// Key image of the output both transactions spend.
+    pub key_image: String,
+    /// Transaction submitted to node `a`.
+    pub first: Transfer,
+    /// Transaction submitted to node `b`.
+    pub second: Transfer,
+}
+
+impl<'c> Network<'c> {
+    /// Starts `nodes` regtest monero containers on a new docker network, each
+    /// monerod peers exclusively with all the others. Fails if `nodes` is less
+    /// than 2.
+    pub fn new(cli: &'c Cli, nodes: u32) -> Result<Self> {
+        if nodes < 2 {
+            bail!("a network needs at least 2 nodes, got {}", nodes);
+        }
+
+        let mut rng = rand::thread_rng();
+        let name = format!("monero-{}", rng.gen::<u32>());
+
+        let status = Command::new("docker")
+            .args(&["network", "create", &name])
+            .status()?;
+        if !status.success() {
+            bail!("failed to create docker network {}", name);
+        }
+
+        let names: Vec<String> = (0..nodes).map(|i| format!("{}-node{}", name, i)).collect();
+
+        let mut network = Self {
+            name: name.clone(),
+            nodes: Vec::new(),
+        };
+
+        for node in &names {
+            let peers = names
+                .iter()
+                .filter(|peer| *peer != node)
+                .map(|peer| format!("{}:{}", peer, MONEROD_P2P_PORT))
+                .collect();
+
+            let monerod_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
+            let wallet_rpc_port: u16 = rng.gen_range(1024, u16::MAX);
+
+            let image = image::Monero::default()
+                .with_exclusive_nodes(peers)
+                .with_mapped_port(Port {
+                    local: monerod_rpc_port,
+                    internal: MONEROD_RPC_PORT,
+                })
+                .with_mapped_port(Port {
+                    local: wallet_rpc_port,
+                    internal: WALLET_RPC_PORT,
+                });
+            let args = RunArgs::default().with_name(node).with_network(&name);
+
+            println!("running image for {} ...", node);
+            let docker = cli.run_with_args(image, args);
+            println!("image ran");
+
+            network.nodes.push(Node {
+                name: node.clone(),
+                docker,
+                monerod_rpc_port,
+                wallet_rpc_port,
+            });
+        }
+
+        Ok(network)
+    }
+
+    /// monerod client for node by index.
+    pub fn monerod_rpc_client(&self, node: usize) -> monerod::Client {
+        monerod::Client::localhost(self.nodes[node].monerod_rpc_port)
+    }
+
+    /// monero-wallet-rpc client for node by index, the wallet-rpc is
+    /// connected to that node's monerod.
+    pub fn wallet_rpc_client(&self, node: usize) -> wallet::Client {
+        wallet::Client::localhost(self.nodes[node].wallet_rpc_port)
+    }
+
+    /// Gets the height and peer connections of every node.
+    pub async fn status(&self) -> Result<Vec<NodeStatus>> {
+        let mut status = Vec::new();
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let monerod = self.monerod_rpc_client(i);
+            let info = monerod.get_info().await?;
+            let connections = monerod.get_connections().await?;
+
+            status.push(NodeStatus {
+                name: node.name.clone(),
+                height: info.height,
+                connections,
+            });
+        }
+
+        Ok(status)
+    }
+
+    /// Waits until every node is connected to all other nodes, fails if they
+    /// are not within `WAIT_TIMEOUT_SECS`.
+    pub async fn wait_for_peers(&self) -> Result<()> {
+        let peers = self.nodes.len() - 1;
+        let start = Instant::now();
+
+        while self
+            .status()
+            .await?
+            .iter()
+            .any(|node| node.connections.len() < peers)
+        {
+            wait(start, "nodes to connect").await?;
+        }
+
+        Ok(())
+    }
+
+    /// IP address of node by index on the docker network.
+    pub fn ip(&self, node: usize) -> Result<String> {
+        let output = Command::new("docker")
+            .args(&[
+                "inspect",
+                "-f",
+                "{{range .NetworkSettings.Networks}}{{.IPAddress}}{{end}}",
+                &self.nodes[node].name,
+            ])
+            .output()?;
+        if !output.status.success() {
+            bail!("failed to inspect container {}", self.nodes[node].name);
+        }
+
+        Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+    }
+
+    /// Partitions nodes `a` and `b` by having each ban the other, returns once
+    /// they are disconnected.
+    pub async fn disconnect(&self, a: usize, b: usize) -> Result<()> {
+        let ip_a = self.ip(a)?;
+        let ip_b = self.ip(b)?;
+
+        self.monerod_rpc_client(a)
+            .set_ban(&ip_b, true, PARTITION_BAN_SECS)
+            .await?;
+        self.monerod_rpc_client(b)
+            .set_ban(&ip_a, true, PARTITION_BAN_SECS)
+            .await?;
+
+        let start = Instant::now();
+        while self.is_connected(a, &ip_b).await? || self.is_connected(b, &ip_a).await? {
+            wait(start, "nodes to disconnect").await?;
+        }
+
+        Ok(())
+    }
+
+    /// Lifts a partition created by `disconnect()`, returns once nodes `a` and
+    /// `b` are connected again.
+    pub async fn reconnect(&self, a: usize, b: usize) -> Result<()> {
+        let ip_a = self.ip(a)?;
+        let ip_b = self.ip(b)?;
+
+        self.monerod_rpc_client(a).set_ban(&ip_b, false, 0).await?;
+        self.monerod_rpc_client(b).set_ban(&ip_a, false, 0).await?;
+
+        let start = Instant::now();
+        while !self.is_connected(a, &ip_b).await? {
+            wait(start, "nodes to reconnect").await?;
+        }
+
+        Ok(())
+    }
+
+    async fn is_connected(&self, node: usize, host: &str) -> Result<bool> {
+        let connections = self.monerod_rpc_client(node).get_connections().await?;
+        Ok(connections.iter().any(|c| c.host == host))
+    }
+
+    /// Simulates a chain reorganization: partitions nodes `a` and `b`, mines
+    /// `a_blocks` on `a` and `b_blocks` on `b` to `reward_address`, then
+    /// reconnects them. The node that mined fewer blocks reorgs onto the
+    /// other node's chain, the lengths must differ since equal length chains
+    /// do not reorg.
+    ///
+    /// Unlike `Monero::reorg()` this does not use `pop_blocks`: popped blocks
+    /// would be fetched from the peers again, and their transactions return
+    /// to the tx pool instead of being orphaned.
+    pub async fn reorg(
+        &self,
+        a: usize,
+        a_blocks: u32,
+        b: usize,
+        b_blocks: u32,
+        reward_address: &str,
+    ) -> Result<Reorg> {
+        if a_blocks == b_blocks {
+            bail!("competing chains must differ in length to reorg");
+        }
+
+        let (winner, loser) = if a_blocks > b_blocks { (a, b) } else { (b, a) };
+
+        self.disconnect(a, b).await?;
+
+        let mined_a = self
+            .monerod_rpc_client(a)
+            .generate_blocks(a_blocks, reward_address)
+            .await?;
+        let mined_b = self
+            .monerod_rpc_client(b)
+            .generate_blocks(b_blocks, reward_address)
+            .await?;
+        let (won, lost) = if winner == a {
+            (mined_a, mined_b)
+        } else {
+            (mined_b, mined_a)
+        };
+
+        self.reconnect(a, b).await?;
+
+        // `get_info()` counts blocks, `won.height` is the height of the last
+        // block mined.
+        let loser_client = self.monerod_rpc_client(loser);
+        let start = Instant::now();
+        while loser_client.get_info().await?.height < won.height + 1 {
+            wait(start, "the losing node to reorg").await?;
+        }
+
+        let orphaned = self.orphaned(loser, &lost).await?;
+
+        Ok(Reorg {
+            winner,
+            depth: u32::try_from(orphaned.len())?,
+            height: won.height + 1,
+            orphaned,
+        })
+    }
+
+    /// Returns the hashes of the blocks in `mined` that are no longer on the
+    /// chain of node by index.
+    pub async fn orphaned(&self, node: usize, mined: &GenerateBlocks) -> Result<Vec<String>> {
+        let monerod = self.monerod_rpc_client(node);
+
+        // `mined.height` is the height of the last block mined.
+        let first = mined.height + 1 - u32::try_from(mined.blocks.len())?;
+        let mut orphaned = Vec::new();
+        for (height, hash) in (first..).zip(&mined.blocks) {
+            let header = monerod.get_block_header_by_height(height).await?;
+            if header.hash != *hash {
+                orphaned.push(hash.clone());
+            }
+        }
+
+        Ok(orphaned)
+    }
+
+    /// Creates two conflicting transactions with the wallet of node `a`, both
+    /// spending the output with `key_image`, one to `address_a` and the other
+    /// to `address_b`. Partitions nodes `a` and `b` and submits one
+    /// transaction to each. Mine on the node that should win, then
+    /// `reconnect()`.
+    pub async fn double_spend(
+        &self,
+        a: usize,
+        b: usize,
+        key_image: &str,
+        address_a: &str,
+        address_b: &str,
+    ) -> Result<DoubleSpend> {
+        let wallet = self.wallet_rpc_client(a);
+
+        self.disconnect(a, b).await?;
+
+        let first = wallet.create_sweep_single(key_image, address_a).await?;
+        let second = wallet.create_sweep_single(key_image, address_b).await?;
+
+        for &(node, tx) in &[(a, &first), (b, &second)] {
+            let res = self
+                .monerod_rpc_client(node)
+                .send_raw_transaction(&tx.tx_blob)
+                .await?;
+            if res.status != "OK" {
+                bail!(
+                    "node {} rejected transaction {}: {}",
+                    node,
+                    tx.tx_hash,
+                    res.reason
+                );
+            }
+        }
+
+        Ok(DoubleSpend {
+            key_image: key_image.to_owned(),
+            first,
+            second,
+        })
+    }
+
+    /// Waits until every node has the block at `height`, e.g.
+    /// `GenerateBlocks::height`.
+    pub async fn wait_for_height(&self, height: u32) -> Result<()> {
+        let start = Instant::now();
+        while self
+            .status()
+            .await?
+            .iter()
+            .any(|node| node.height <= height)
+        {
+            wait(start, "nodes to sync").await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Network<'_> {
+    fn drop(&mut self) {
+        // The containers must be removed before the network can be.
+        self.nodes.clear();
+
+        let _ = Command::new("docker")
+            .args(&["network", "rm", &self.name])
+            .status();
+    }
+}
+
+/// Sleeps before polling again, fails once `start` was longer than
+/// `WAIT_TIMEOUT_SECS` ago.
+async fn wait(start: Instant, what: &str) -> Result<()> {
+    if start.elapsed() > Duration::from_secs(WAIT_TIMEOUT_SECS) {
+        bail!("timed out waiting for {}", what);
+    }
+    time::delay_for(Duration::from_millis(WAIT_PEERS_MILLIS)).await;
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Fault-injecting HTTP proxy to sit between the RPC clients and monerod or
+//! monero-wallet-rpc.
+//!
+//! Point a client at the proxy, e.g. `monerod::Client::localhost(proxy.port)`,
+//! and each request is forwarded upstream or faulted according to the proxy's
+//! `Policy`.
+
+use anyhow::{anyhow, Result};
+use futures::future;
+use hyper::{
+    header::{HeaderMap, CONNECTION, HOST, TRANSFER_ENCODING},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use reqwest::Url;
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::time;
+
+use crate::server::Running;
+
+/// What the proxy does with a single request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Forward the request upstream unmodified.
+    None,
+    /// Forward the request upstream after a delay.
+    Latency(Duration),
+    /// Close the connection without responding.
+    Drop,
+    /// Respond with HTTP 500 without forwarding.
+    InternalServerError,
+    /// Respond with HTTP 200 and a body that is not valid JSON, without
+    /// forwarding.
+    MalformedJson,
+    /// Never respond.
+    Stall,
+}
+
+/// Scripted faults, the n-th request gets the n-th fault of the script and
+/// requests after the end of the script get the default fault.
+#[derive(Clone, Debug)]
+pub struct Policy {
+    script: VecDeque<Fault>,
+    default: Fault,
+}
+
+impl Policy {
+    /// Policy that runs through `script` then forwards all requests.
+    pub fn new(script: Vec<Fault>) -> Self {
+        Self {
+            script: script.into(),
+            default: Fault::None,
+        }
+    }
+
+    /// Policy that applies `fault` to every request.
+    pub fn always(fault: Fault) -> Self {
+        Self {
+            script: VecDeque::new(),
+            default: fault,
+        }
+    }
+
+    /// Applies `fault` to requests after the end of the script.
+    pub fn with_default(self, fault: Fault) -> Self {
+        Self {
+            default: fault,
+            ..self
+        }
+    }
+
+    fn next(&mut self) -> Fault {
+        self.script.pop_front().unwrap_or(self.default)
+    }
+}
+
+/// Forwards every request.
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::new(vec![])
+    }
+}
+
+/// A running proxy, stops when dropped.
+#[derive(Debug)]
+pub struct Proxy {
+    /// Local port the proxy listens on.
+    pub port: u16,
+    state: Arc<State>,
+    _server: Running,
+}
+
+#[derive(Debug)]
+struct State {
+    upstream: Url,
+    policy: Mutex<Policy>,
+    requests: AtomicUsize,
+    client: reqwest::Client,
+}
+
+impl Proxy {
+    /// Starts a proxy on a random localhost port forwarding to `upstream`,
+    /// e.g. `http://127.0.0.1:18081`. Request methods, paths, queries and
+    /// headers are kept, so clients can use the `/json_rpc` endpoint and
+    /// digest authentication as usual.
+    pub async fn start(upstream: Url, policy: Policy) -> Result<Self> {
+        let state = Arc::new(State {
+            upstream,
+            policy: Mutex::new(policy),
+            requests: AtomicUsize::new(0),
+            client: reqwest::Client::new(),
+        });
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let state = state.clone();
+                    async move { state.handle(request).await }
+                }))
+            }
+        });
+
+        let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+        let server = Server::try_bind(&addr)?.serve(make_service);
+        let server = Running::spawn(server.local_addr().port(), server);
+
+        Ok(Self {
+            port: server.port,
+            state,
+            _server: server,
+        })
+    }
+
+    /// Replaces the policy, applies from the next request.
+    pub fn set_policy(&self, policy: Policy) {
+        *self.state.policy.lock().expect("poisoned lock") = policy;
+    }
+
+    /// Number of requests received so far, including faulted ones.
+    pub fn requests(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+}
+
+impl State {
+    async fn handle(&self, request: Request<Body>) -> Result<Response<Body>> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        let fault = self.policy.lock().expect("poisoned lock").next();
+
+        match fault {
+            Fault::None => self.forward(request).await,
+            Fault::Latency(delay) => {
+                time::delay_for(delay).await;
+                self.forward(request).await
+            }
+            // Returning an error makes hyper close the connection.
+            Fault::Drop => Err(anyhow!("dropping connection")),
+            Fault::InternalServerError => Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from("Internal Server Error"))?),
+            Fault::MalformedJson => Ok(Response::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "application/json")
+                .body(Body::from("{\"id\":\"1\",\"jsonrpc\":\"2.0\",\"result\":{"))?),
+            Fault::Stall => {
+                future::pending::<()>().await;
+                unreachable!("pending future never resolves")
+            }
+        }
+    }
+
+    async fn forward(&self, request: Request<Body>) -> Result<Response<Body>> {
+        let (parts, body) = request.into_parts();
+        let mut url = self.upstream.join(parts.uri.path())?;
+        url.set_query(parts.uri.query());
+        let body = hyper::body::to_bytes(body).await?;
+
+        let mut headers = parts.headers;
+        // The client sets the host of the upstream.
+        headers.remove(HOST);
+        strip_hop_by_hop(&mut headers);
+
+        let upstream = self
+            .client
+            .request(parts.method, url)
+            .headers(headers)
+            .body(body.to_vec())
+            .send()
+            .await?;
+
+        let mut response = Response::builder().status(upstream.status());
+        if let Some(headers) = response.headers_mut() {
+            *headers = upstream.headers().clone();
+            strip_hop_by_hop(headers);
+        }
+        let body = upstream.bytes().await?;
+
+        Ok(response.body(Body::from(body))?)
+    }
+}
+
+/// Removes headers that only apply to a single connection, bodies are read
+/// in full and sent on with a new connection.
+fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    headers.remove(CONNECTION);
+    headers.remove(TRANSFER_ENCODING);
+}
@@ -1,8 +1,91 @@
 //! JSON RPC clients for `monerd` and `monero-wallet-rpc`.
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub(crate) mod digest;
 pub mod monerod;
+pub(crate) mod tls;
 pub mod wallet;
 
+pub use digest::Credentials;
+pub use tls::{Certificate, Tls};
+
+use anyhow::{bail, Result};
+use reqwest::{header::AUTHORIZATION, StatusCode, Url};
 use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, time::Duration};
+use tokio::time;
+
+#[cfg(feature = "cassette")]
+use crate::cassette::Cassette;
+use crate::rpc::digest::DigestAuth;
+
+/// Settings of the HTTP client behind an RPC client. The default neither
+/// times out nor retries, like the clients did before the config existed.
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+    /// Timeout for establishing a connection, none to wait as long as the
+    /// OS does.
+    pub connect_timeout: Option<Duration>,
+    /// Timeout for a whole request, from connecting until the response body
+    /// is read, none to wait forever.
+    pub timeout: Option<Duration>,
+    /// How often a call that only reads is retried after a connection error,
+    /// a timeout or an HTTP 5xx response. Calls that change state are never
+    /// retried.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for every further retry.
+    pub retry_backoff: Duration,
+    pub user_agent: String,
+    /// How long an idle connection is kept for reuse, none to keep it until
+    /// the server closes it.
+    pub pool_idle_timeout: Option<Duration>,
+    pub pool_max_idle_per_host: usize,
+    /// Certificate verification for an `https` endpoint, none for `http`.
+    pub tls: Option<Tls>,
+    /// Records requests and responses to, or replays responses from, a
+    /// cassette file instead of the network.
+    #[cfg(feature = "cassette")]
+    pub cassette: Option<Cassette>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: None,
+            timeout: None,
+            retries: 0,
+            retry_backoff: Duration::from_millis(250),
+            user_agent: concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")).to_owned(),
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+            tls: None,
+            #[cfg(feature = "cassette")]
+            cassette: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Builds the HTTP client for `url`.
+    pub(crate) fn build(&self, url: &Url) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(tls) = &self.tls {
+            builder = tls::configure(builder, url, tls)?;
+        }
+
+        Ok(builder.build()?)
+    }
+}
 
 #[derive(Serialize, Debug, Clone)]
 pub struct Request<T> {
@@ -36,6 +119,161 @@ struct Response<T> {
     pub result: T,
 }
 
+/// Posts `body` as JSON to `url` and returns the response body, or records
+/// or replays the request with the cassette of `config`. With `auth` a
+/// digest challenge is answered by resending the request, later requests are
+/// authenticated up front.
+async fn post<T: Serialize>(
+    inner: &reqwest::Client,
+    auth: Option<&DigestAuth>,
+    config: &ClientConfig,
+    url: Url,
+    body: &T,
+) -> Result<String> {
+    let body = serde_json::to_vec(body)?;
+
+    let (status, response) = match replayed(config, &url, &body)? {
+        Some(replayed) => replayed,
+        None => {
+            let (status, response) = send(inner, auth, &url, &body).await?;
+            record(config, &url, &body, status, &response)?;
+            (status, response)
+        }
+    };
+
+    if status.is_server_error() {
+        return Err(ServerError { url, status }.into());
+    }
+
+    Ok(response)
+}
+
+/// Sends `body` to `url`, answering a digest challenge with `auth`, returns
+/// the response status and body.
+async fn send(
+    inner: &reqwest::Client,
+    auth: Option<&DigestAuth>,
+    url: &Url,
+    body: &[u8],
+) -> Result<(StatusCode, String)> {
+    let uri = url.path().to_owned();
+
+    let send = |authorization: Option<String>| {
+        let mut request = inner
+            .post(url.clone())
+            .header("content-type", "application/json")
+            .body(body.to_vec());
+        if let Some(authorization) = authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        request.send()
+    };
+
+    let mut response = send(auth.and_then(|auth| auth.authorization("POST", &uri))).await?;
+
+    // The first request, or one with a stale nonce, gets a new challenge.
+    if response.status() == StatusCode::UNAUTHORIZED {
+        let auth = match auth {
+            Some(auth) => auth,
+            None => bail!("{} requires authentication, no credentials set", url),
+        };
+        auth.challenge(response.headers())?;
+        response = send(auth.authorization("POST", &uri)).await?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            bail!("{} rejected the credentials", url);
+        }
+    }
+
+    let status = response.status();
+    Ok((status, response.text().await?))
+}
+
+/// The recorded response to the request if `config` replays a cassette.
+#[cfg(feature = "cassette")]
+fn replayed(config: &ClientConfig, url: &Url, body: &[u8]) -> Result<Option<(StatusCode, String)>> {
+    match &config.cassette {
+        Some(cassette) => cassette.replayed(url.path(), body),
+        None => Ok(None),
+    }
+}
+
+#[cfg(not(feature = "cassette"))]
+fn replayed(_: &ClientConfig, _: &Url, _: &[u8]) -> Result<Option<(StatusCode, String)>> {
+    Ok(None)
+}
+
+/// Records the request and its response if `config` records a cassette.
+#[cfg(feature = "cassette")]
+fn record(
+    config: &ClientConfig,
+    url: &Url,
+    body: &[u8],
+    status: StatusCode,
+    response: &str,
+) -> Result<()> {
+    match &config.cassette {
+        Some(cassette) => cassette.record_interaction(url.path(), body, status, response),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "cassette"))]
+fn record(_: &ClientConfig, _: &Url, _: &[u8], _: StatusCode, _: &str) -> Result<()> {
+    Ok(())
+}
+
+/// Like `post()` but retries transient failures according to `config`, for
+/// calls that only read.
+async fn post_idempotent<T: Serialize>(
+    inner: &reqwest::Client,
+    auth: Option<&DigestAuth>,
+    config: &ClientConfig,
+    url: Url,
+    body: &T,
+) -> Result<String> {
+    let mut backoff = config.retry_backoff;
+
+    for _ in 0..config.retries {
+        match post(inner, auth, config, url.clone(), body).await {
+            Err(e) if is_transient(&e) => {
+                time::delay_for(backoff).await;
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    post(inner, auth, config, url, body).await
+}
+
+/// Whether a request failed in a way that may not happen again.
+fn is_transient(error: &anyhow::Error) -> bool {
+    if error.downcast_ref::<ServerError>().is_some() {
+        return true;
+    }
+
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+        None => false,
+    }
+}
+
+/// An HTTP 5xx response.
+#[derive(Debug)]
+struct ServerError {
+    url: Url,
+    status: StatusCode,
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} responded with {}", self.url, self.status)
+    }
+}
+
+impl Error for ServerError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,35 @@
+//! Blocking variants of the RPC clients, for code without an async runtime.
+//!
+//! Each client wraps its async counterpart and runs every call to completion
+//! on a private single threaded tokio runtime, so methods, response types and
+//! errors are the same. Like `reqwest::blocking`, the clients panic when used
+//! from within an async runtime.
+pub mod monerod;
+pub mod wallet;
+
+use std::{future::Future, sync::Mutex};
+
+/// Runtime of a blocking client. Calls from several threads through a shared
+/// client run one at a time.
+#[derive(Debug)]
+struct Runtime {
+    inner: Mutex<tokio::runtime::Runtime>,
+}
+
+impl Runtime {
+    fn new() -> Self {
+        let runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .expect("failed to start tokio runtime");
+
+        Self {
+            inner: Mutex::new(runtime),
+        }
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.inner.lock().expect("poisoned lock").block_on(future)
+    }
+}
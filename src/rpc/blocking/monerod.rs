@@ -0,0 +1,126 @@
+//! Blocking monerod client.
+
+use anyhow::Result;
+use reqwest::Url;
+use std::sync::Arc;
+
+use crate::{
+    rpc::{
+        blocking::Runtime,
+        monerod::{
+            self, Connection, FeeEstimate, GenerateBlocks, GetInfo, GetTransactions, PopBlocks,
+            SendRawTransaction,
+        },
+        ClientConfig, Credentials, Tls,
+    },
+    BlockHeader,
+};
+
+/// Blocking JSON RPC client for monerod, see `rpc::monerod::Client`.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: monerod::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Client for monerod on localhost `port`.
+    pub fn localhost(port: u16) -> Self {
+        Client::wrap(monerod::Client::localhost(port))
+    }
+
+    /// Client for monerod at `url`.
+    pub fn new(url: Url) -> Self {
+        Client::wrap(monerod::Client::new(url))
+    }
+
+    /// Client for monerod on localhost `port` started with `--rpc-ssl`.
+    pub fn localhost_with_tls(port: u16, tls: Tls) -> Result<Self> {
+        Ok(Client::wrap(monerod::Client::localhost_with_tls(
+            port, tls,
+        )?))
+    }
+
+    /// Client for monerod at `https` `url`, verifying the certificate
+    /// according to `tls`.
+    pub fn with_tls(url: Url, tls: Tls) -> Result<Self> {
+        Ok(Client::wrap(monerod::Client::with_tls(url, tls)?))
+    }
+
+    /// Client for monerod at `url` with HTTP client settings `config`.
+    pub fn with_config(url: Url, config: ClientConfig) -> Result<Self> {
+        Ok(Client::wrap(monerod::Client::with_config(url, config)?))
+    }
+
+    /// Authenticates requests with `credentials`, for a monerod started
+    /// with `--rpc-login`.
+    pub fn with_credentials(self, credentials: Credentials) -> Self {
+        Self {
+            inner: self.inner.with_credentials(credentials),
+            ..self
+        }
+    }
+
+    fn wrap(inner: monerod::Client) -> Self {
+        Self {
+            inner,
+            runtime: Arc::new(Runtime::new()),
+        }
+    }
+
+    pub fn generate_blocks(
+        &self,
+        amount_of_blocks: u32,
+        wallet_address: &str,
+    ) -> Result<GenerateBlocks> {
+        self.runtime
+            .block_on(self.inner.generate_blocks(amount_of_blocks, wallet_address))
+    }
+
+    pub fn get_block_header_by_height(&self, height: u32) -> Result<BlockHeader> {
+        self.runtime
+            .block_on(self.inner.get_block_header_by_height(height))
+    }
+
+    /// Get the per byte fee estimate for transactions to be mined within the
+    /// next few blocks.
+    pub fn get_fee_estimate(&self) -> Result<FeeEstimate> {
+        self.runtime.block_on(self.inner.get_fee_estimate())
+    }
+
+    /// Get general information about the state of the node.
+    pub fn get_info(&self) -> Result<GetInfo> {
+        self.runtime.block_on(self.inner.get_info())
+    }
+
+    /// Get the node's peer connections.
+    pub fn get_connections(&self) -> Result<Vec<Connection>> {
+        self.runtime.block_on(self.inner.get_connections())
+    }
+
+    /// Ban (or unban if `ban` is false) peer `host` (IP address) for
+    /// `seconds`, disconnecting it if connected.
+    pub fn set_ban(&self, host: &str, ban: bool, seconds: u32) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.set_ban(host, ban, seconds))
+    }
+
+    /// Pop `nblocks` blocks off the top of the chain, their transactions are
+    /// returned to the tx pool.
+    pub fn pop_blocks(&self, nblocks: u32) -> Result<PopBlocks> {
+        self.runtime.block_on(self.inner.pop_blocks(nblocks))
+    }
+
+    /// Submit a transaction to the node, `tx_as_hex` is e.g.
+    /// `wallet::Transfer::tx_blob`.
+    pub fn send_raw_transaction(&self, tx_as_hex: &str) -> Result<SendRawTransaction> {
+        self.runtime
+            .block_on(self.inner.send_raw_transaction(tx_as_hex))
+    }
+
+    /// Look up transactions by hash in the chain and the tx pool.
+    pub fn get_transactions(&self, tx_hashes: Vec<String>) -> Result<GetTransactions> {
+        self.runtime
+            .block_on(self.inner.get_transactions(tx_hashes))
+    }
+}
@@ -0,0 +1,538 @@
+//! Blocking monero-wallet-rpc client.
+
+use anyhow::Result;
+use reqwest::Url;
+use std::sync::Arc;
+
+use crate::rpc::{
+    blocking::Runtime,
+    wallet::{
+        self, AddressBookEntry, BlockHeight, CheckTxKey, CreateAccount, CreateAddress, Destination,
+        ExchangeMultisigKeys, ExportKeyImages, GenerateFromKeys, GetAccounts, GetAddress,
+        ImportKeyImages, IncomingTransfer, IntegratedAddress, IsMultisig, KeyType, MakeMultisig,
+        PaymentUri, Priority, Refreshed, SignMultisig, SignTransfer, SignedKeyImage,
+        SplitIntegratedAddress, SubAddressIndex, Transfer, TransferDescription, TransferType,
+        TxSizeAndWeight, ValidateAddress, Verify, Version,
+    },
+    ClientConfig, Credentials, Tls,
+};
+
+/// Blocking JSON RPC client for monero-wallet-rpc, see `rpc::wallet::Client`.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: wallet::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Client for monero-wallet-rpc on localhost `port`.
+    pub fn localhost(port: u16) -> Self {
+        Client::wrap(wallet::Client::localhost(port))
+    }
+
+    /// Client for monero-wallet-rpc at `url`.
+    pub fn new(url: Url) -> Self {
+        Client::wrap(wallet::Client::new(url))
+    }
+
+    /// Client for monero-wallet-rpc on localhost `port` started with
+    /// `--rpc-ssl`.
+    pub fn localhost_with_tls(port: u16, tls: Tls) -> Result<Self> {
+        Ok(Client::wrap(wallet::Client::localhost_with_tls(port, tls)?))
+    }
+
+    /// Client for monero-wallet-rpc at `https` `url`, verifying the certificate
+    /// according to `tls`.
+    pub fn with_tls(url: Url, tls: Tls) -> Result<Self> {
+        Ok(Client::wrap(wallet::Client::with_tls(url, tls)?))
+    }
+
+    /// Client for monero-wallet-rpc at `url` with HTTP client settings
+    /// `config`.
+    pub fn with_config(url: Url, config: ClientConfig) -> Result<Self> {
+        Ok(Client::wrap(wallet::Client::with_config(url, config)?))
+    }
+
+    /// Authenticates requests with `credentials`, for a monero-wallet-rpc
+    /// started with `--rpc-login`.
+    pub fn with_credentials(self, credentials: Credentials) -> Self {
+        Self {
+            inner: self.inner.with_credentials(credentials),
+            ..self
+        }
+    }
+
+    fn wrap(inner: wallet::Client) -> Self {
+        Self {
+            inner,
+            runtime: Arc::new(Runtime::new()),
+        }
+    }
+
+    /// Get addresses for account by index, the subaddresses with
+    /// `address_indices` or all subaddresses if none.
+    pub fn get_address(
+        &self,
+        account_index: u32,
+        address_indices: Option<Vec<u32>>,
+    ) -> Result<GetAddress> {
+        self.runtime
+            .block_on(self.inner.get_address(account_index, address_indices))
+    }
+
+    /// Gets the balance of account by index.
+    pub fn get_balance(&self, index: u32) -> Result<u64> {
+        self.runtime.block_on(self.inner.get_balance(index))
+    }
+
+    pub fn create_account(&self, label: &str) -> Result<CreateAccount> {
+        self.runtime.block_on(self.inner.create_account(label))
+    }
+
+    /// Create `count` new subaddresses in account by index.
+    pub fn create_address(
+        &self,
+        account_index: u32,
+        label: &str,
+        count: u32,
+    ) -> Result<CreateAddress> {
+        self.runtime
+            .block_on(self.inner.create_address(account_index, label, count))
+    }
+
+    /// Get the account and subaddress index of a wallet `address`.
+    pub fn get_address_index(&self, address: &str) -> Result<SubAddressIndex> {
+        self.runtime.block_on(self.inner.get_address_index(address))
+    }
+
+    /// Set the label of the subaddress at `index`.
+    pub fn label_address(&self, index: SubAddressIndex, label: &str) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.label_address(index, label))
+    }
+
+    /// Set the label of account by index.
+    pub fn label_account(&self, account_index: u32, label: &str) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.label_account(account_index, label))
+    }
+
+    /// Tag `accounts` with `tag`, see `get_accounts()` for filtering by tag.
+    pub fn tag_accounts(&self, tag: &str, accounts: Vec<u32>) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.tag_accounts(tag, accounts))
+    }
+
+    /// Remove the tag from `accounts`.
+    pub fn untag_accounts(&self, accounts: Vec<u32>) -> Result<()> {
+        self.runtime.block_on(self.inner.untag_accounts(accounts))
+    }
+
+    /// Set the description of an account `tag`.
+    pub fn set_account_tag_description(&self, tag: &str, description: &str) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.set_account_tag_description(tag, description))
+    }
+
+    /// Make an integrated address from `standard_address` (the primary
+    /// address if empty) and `payment_id` (random if empty).
+    pub fn make_integrated_address(
+        &self,
+        standard_address: &str,
+        payment_id: &str,
+    ) -> Result<IntegratedAddress> {
+        self.runtime.block_on(
+            self.inner
+                .make_integrated_address(standard_address, payment_id),
+        )
+    }
+
+    /// Split an integrated address into its standard address and payment id.
+    pub fn split_integrated_address(
+        &self,
+        integrated_address: &str,
+    ) -> Result<SplitIntegratedAddress> {
+        self.runtime
+            .block_on(self.inner.split_integrated_address(integrated_address))
+    }
+
+    /// Validate `address`, only addresses on the wallet's network type are
+    /// valid unless `any_net_type` is set.
+    pub fn validate_address(&self, address: &str, any_net_type: bool) -> Result<ValidateAddress> {
+        self.runtime
+            .block_on(self.inner.validate_address(address, any_net_type))
+    }
+
+    /// Make a `monero:` payment URI.
+    pub fn make_uri(&self, uri: &PaymentUri) -> Result<String> {
+        self.runtime.block_on(self.inner.make_uri(uri))
+    }
+
+    /// Parse a `monero:` payment URI.
+    pub fn parse_uri(&self, uri: &str) -> Result<PaymentUri> {
+        self.runtime.block_on(self.inner.parse_uri(uri))
+    }
+
+    /// Get accounts, filtered by tag ("" for no filtering).
+    pub fn get_accounts(&self, tag: &str) -> Result<GetAccounts> {
+        self.runtime.block_on(self.inner.get_accounts(tag))
+    }
+
+    /// Creates a wallet using `filename`.
+    pub fn create_wallet(&self, filename: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.create_wallet(filename))
+    }
+
+    /// Opens the wallet `filename`, closing the currently open wallet.
+    pub fn open_wallet(&self, filename: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.open_wallet(filename))
+    }
+
+    /// Closes the currently open wallet.
+    pub fn close_wallet(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.close_wallet())
+    }
+
+    /// Transfers `amount` moneroj from `account_index` to `address`.
+    pub fn transfer(&self, account_index: u32, amount: u64, address: &str) -> Result<Transfer> {
+        self.runtime
+            .block_on(self.inner.transfer(account_index, amount, address))
+    }
+
+    /// Transfers `amount` moneroj from `account_index` to `address`, paying
+    /// fees according to `priority`.
+    pub fn transfer_with_priority(
+        &self,
+        account_index: u32,
+        amount: u64,
+        address: &str,
+        priority: Priority,
+    ) -> Result<Transfer> {
+        self.runtime.block_on(self.inner.transfer_with_priority(
+            account_index,
+            amount,
+            address,
+            priority,
+        ))
+    }
+
+    /// Transfers moneroj from `account_index` to `destinations`.
+    pub fn multi_transfer(
+        &self,
+        account_index: u32,
+        destinations: Vec<Destination>,
+    ) -> Result<Transfer> {
+        self.runtime
+            .block_on(self.inner.multi_transfer(account_index, destinations))
+    }
+
+    /// Transfers moneroj from `account_index` to `destinations`, paying fees
+    /// according to `priority`.
+    pub fn multi_transfer_with_priority(
+        &self,
+        account_index: u32,
+        destinations: Vec<Destination>,
+        priority: Priority,
+    ) -> Result<Transfer> {
+        self.runtime
+            .block_on(self.inner.multi_transfer_with_priority(
+                account_index,
+                destinations,
+                priority,
+            ))
+    }
+
+    /// Get outputs received by account by index, filtered by `transfer_type`.
+    pub fn incoming_transfers(
+        &self,
+        transfer_type: TransferType,
+        account_index: u32,
+    ) -> Result<Vec<IncomingTransfer>> {
+        self.runtime
+            .block_on(self.inner.incoming_transfers(transfer_type, account_index))
+    }
+
+    /// Sends the single output with `key_image` to `address`.
+    pub fn sweep_single(&self, key_image: &str, address: &str) -> Result<Transfer> {
+        self.runtime
+            .block_on(self.inner.sweep_single(key_image, address))
+    }
+
+    /// Creates, but does not relay, a transaction sending the single output
+    /// with `key_image` to `address`. The output is not marked as spent so
+    /// this can be used to create conflicting transactions,
+    /// `Transfer::tx_blob` holds the transaction.
+    pub fn create_sweep_single(&self, key_image: &str, address: &str) -> Result<Transfer> {
+        self.runtime
+            .block_on(self.inner.create_sweep_single(key_image, address))
+    }
+
+    /// Freeze the output with `key_image` so it is not used in transfers.
+    pub fn freeze(&self, key_image: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.freeze(key_image))
+    }
+
+    /// Thaw a frozen output with `key_image`.
+    pub fn thaw(&self, key_image: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.thaw(key_image))
+    }
+
+    /// Check if the output with `key_image` is frozen.
+    pub fn frozen(&self, key_image: &str) -> Result<bool> {
+        self.runtime.block_on(self.inner.frozen(key_image))
+    }
+
+    /// Estimate the size and weight of a transaction with `n_inputs` and
+    /// `n_outputs`.
+    pub fn estimate_tx_size_and_weight(
+        &self,
+        n_inputs: u32,
+        n_outputs: u32,
+        ring_size: u32,
+    ) -> Result<TxSizeAndWeight> {
+        self.runtime.block_on(
+            self.inner
+                .estimate_tx_size_and_weight(n_inputs, n_outputs, ring_size),
+        )
+    }
+
+    /// Get wallet block height, this might be behind monerod height.
+    pub fn block_height(&self) -> Result<BlockHeight> {
+        self.runtime.block_on(self.inner.block_height())
+    }
+
+    /// Refresh the open wallet, from `start_height` if some, returns once the
+    /// wallet has synced with the daemon.
+    pub fn refresh(&self, start_height: Option<u32>) -> Result<Refreshed> {
+        self.runtime.block_on(self.inner.refresh(start_height))
+    }
+
+    /// Enable or disable background refreshing of the open wallet, every
+    /// `period` seconds if some.
+    pub fn auto_refresh(&self, enable: bool, period: Option<u32>) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.auto_refresh(enable, period))
+    }
+
+    /// Rescan the blockchain from scratch, a `hard` rescan also drops
+    /// spent/unspent status and transaction notes.
+    pub fn rescan_blockchain(&self, hard: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.rescan_blockchain(hard))
+    }
+
+    /// Rescan the blockchain for spent outputs.
+    pub fn rescan_spent(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.rescan_spent())
+    }
+
+    /// Connect the wallet to the daemon at `address` (host:port).
+    pub fn set_daemon(&self, address: &str, trusted: bool) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.set_daemon(address, trusted))
+    }
+
+    /// Get the monero-wallet-rpc RPC version.
+    pub fn get_version(&self) -> Result<Version> {
+        self.runtime.block_on(self.inner.get_version())
+    }
+
+    /// Check a transaction in the blockchain with its secret key.
+    pub fn check_tx_key(&self, tx_id: &str, tx_key: &str, address: &str) -> Result<CheckTxKey> {
+        self.runtime
+            .block_on(self.inner.check_tx_key(tx_id, tx_key, address))
+    }
+
+    /// Check if the open wallet is a multisig wallet.
+    pub fn is_multisig(&self) -> Result<IsMultisig> {
+        self.runtime.block_on(self.inner.is_multisig())
+    }
+
+    /// Prepare the open wallet for multisig, returns the multisig info to
+    /// share with the other participants.
+    pub fn prepare_multisig(&self) -> Result<String> {
+        self.runtime.block_on(self.inner.prepare_multisig())
+    }
+
+    /// Make a `threshold`-of-N multisig wallet from the other participants'
+    /// `multisig_info`.
+    pub fn make_multisig(
+        &self,
+        multisig_info: Vec<String>,
+        threshold: u32,
+    ) -> Result<MakeMultisig> {
+        self.runtime
+            .block_on(self.inner.make_multisig(multisig_info, threshold))
+    }
+
+    /// Perform a round of multisig key exchange, required for M-of-N wallets
+    /// where M < N.
+    pub fn exchange_multisig_keys(
+        &self,
+        multisig_info: Vec<String>,
+    ) -> Result<ExchangeMultisigKeys> {
+        self.runtime
+            .block_on(self.inner.exchange_multisig_keys(multisig_info))
+    }
+
+    /// Finalize an (N-1)-of-N multisig wallet, returns the multisig address.
+    pub fn finalize_multisig(&self, multisig_info: Vec<String>) -> Result<String> {
+        self.runtime
+            .block_on(self.inner.finalize_multisig(multisig_info))
+    }
+
+    /// Export multisig info for the other participants, required before
+    /// spending from a multisig wallet.
+    pub fn export_multisig_info(&self) -> Result<String> {
+        self.runtime.block_on(self.inner.export_multisig_info())
+    }
+
+    /// Import multisig info exported by the other participants, returns the
+    /// number of outputs signed with the imported info.
+    pub fn import_multisig_info(&self, info: Vec<String>) -> Result<u32> {
+        self.runtime.block_on(self.inner.import_multisig_info(info))
+    }
+
+    /// Sign a multisig transaction set, e.g. `Transfer::multisig_txset`.
+    pub fn sign_multisig(&self, tx_data_hex: &str) -> Result<SignMultisig> {
+        self.runtime.block_on(self.inner.sign_multisig(tx_data_hex))
+    }
+
+    /// Submit a fully signed multisig transaction set, returns the
+    /// transaction hashes.
+    pub fn submit_multisig(&self, tx_data_hex: &str) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.submit_multisig(tx_data_hex))
+    }
+
+    /// Creates a wallet from keys and opens it, omitting `spend_key` creates a
+    /// view-only wallet.
+    pub fn generate_from_keys(
+        &self,
+        filename: &str,
+        address: &str,
+        spend_key: Option<&str>,
+        view_key: &str,
+        restore_height: u32,
+    ) -> Result<GenerateFromKeys> {
+        self.runtime.block_on(self.inner.generate_from_keys(
+            filename,
+            address,
+            spend_key,
+            view_key,
+            restore_height,
+        ))
+    }
+
+    /// Query the mnemonic seed or a private key of the open wallet.
+    pub fn query_key(&self, key_type: KeyType) -> Result<String> {
+        self.runtime.block_on(self.inner.query_key(key_type))
+    }
+
+    /// Sign `data` with the spend key of the subaddress at `index`.
+    pub fn sign(&self, data: &str, index: SubAddressIndex) -> Result<String> {
+        self.runtime.block_on(self.inner.sign(data, index))
+    }
+
+    /// Verify a `signature` of `data` by `address`.
+    pub fn verify(&self, data: &str, address: &str, signature: &str) -> Result<Verify> {
+        self.runtime
+            .block_on(self.inner.verify(data, address, signature))
+    }
+
+    /// Get a custom attribute of the open wallet.
+    pub fn get_attribute(&self, key: &str) -> Result<String> {
+        self.runtime.block_on(self.inner.get_attribute(key))
+    }
+
+    /// Set a custom attribute of the open wallet.
+    pub fn set_attribute(&self, key: &str, value: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.set_attribute(key, value))
+    }
+
+    /// Set notes for transactions, `tx_ids` and `notes` are matched by
+    /// position.
+    pub fn set_tx_notes(&self, tx_ids: Vec<String>, notes: Vec<String>) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.set_tx_notes(tx_ids, notes))
+    }
+
+    /// Get notes for transactions, in the same order as `tx_ids`.
+    pub fn get_tx_notes(&self, tx_ids: Vec<String>) -> Result<Vec<String>> {
+        self.runtime.block_on(self.inner.get_tx_notes(tx_ids))
+    }
+
+    /// Get address book entries by index (empty for all entries).
+    pub fn get_address_book(&self, entries: Vec<u32>) -> Result<Vec<AddressBookEntry>> {
+        self.runtime.block_on(self.inner.get_address_book(entries))
+    }
+
+    /// Add an entry to the address book, returns the index of the new entry.
+    pub fn add_address_book(&self, address: &str, description: &str) -> Result<u32> {
+        self.runtime
+            .block_on(self.inner.add_address_book(address, description))
+    }
+
+    /// Edit the address book entry at `index`, fields that are none are left
+    /// unchanged.
+    pub fn edit_address_book(
+        &self,
+        index: u32,
+        address: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.edit_address_book(index, address, description))
+    }
+
+    /// Delete the address book entry at `index`.
+    pub fn delete_address_book(&self, index: u32) -> Result<()> {
+        self.runtime.block_on(self.inner.delete_address_book(index))
+    }
+
+    /// Describe an unsigned transaction set, e.g. `Transfer::unsigned_txset`.
+    pub fn describe_transfer(&self, unsigned_txset: &str) -> Result<Vec<TransferDescription>> {
+        self.runtime
+            .block_on(self.inner.describe_transfer(unsigned_txset))
+    }
+
+    /// Sign an unsigned transaction set created by a view-only wallet.
+    pub fn sign_transfer(&self, unsigned_txset: &str) -> Result<SignTransfer> {
+        self.runtime
+            .block_on(self.inner.sign_transfer(unsigned_txset))
+    }
+
+    /// Submit a signed transaction set, e.g. `SignTransfer::signed_txset`,
+    /// returns the transaction hashes.
+    pub fn submit_transfer(&self, tx_data_hex: &str) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.submit_transfer(tx_data_hex))
+    }
+
+    /// Export outputs of the open wallet, usually a view-only wallet, as hex.
+    pub fn export_outputs(&self, all: bool) -> Result<String> {
+        self.runtime.block_on(self.inner.export_outputs(all))
+    }
+
+    /// Import outputs exported by `export_outputs()`, returns the number of
+    /// outputs imported.
+    pub fn import_outputs(&self, outputs_data_hex: &str) -> Result<u32> {
+        self.runtime
+            .block_on(self.inner.import_outputs(outputs_data_hex))
+    }
+
+    /// Export signed key images of the open wallet, usually the signing
+    /// wallet.
+    pub fn export_key_images(&self, all: bool) -> Result<ExportKeyImages> {
+        self.runtime.block_on(self.inner.export_key_images(all))
+    }
+
+    /// Import signed key images, allows a view-only wallet to see spends.
+    pub fn import_key_images(
+        &self,
+        offset: u32,
+        signed_key_images: Vec<SignedKeyImage>,
+    ) -> Result<ImportKeyImages> {
+        self.runtime
+            .block_on(self.inner.import_key_images(offset, signed_key_images))
+    }
+}
@@ -0,0 +1,385 @@
+//! HTTP digest authentication (RFC 7616) as used by monerod and
+//! monero-wallet-rpc started with `--rpc-login`.
+//!
+//! Only MD5 and MD5-sess with `qop=auth` or without qop are supported, which
+//! is what epee, the HTTP server of both daemons, offers.
+
+use anyhow::{anyhow, bail, Result};
+use rand::Rng;
+use reqwest::header::{HeaderMap, WWW_AUTHENTICATE};
+use std::{fmt, sync::Mutex};
+
+/// Username and password for an RPC server started with `--rpc-login`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+
+    /// Credentials as passed to `--rpc-login`.
+    pub fn login(&self) -> String {
+        format!("{}:{}", self.username, self.password)
+    }
+}
+
+/// Keeps the password out of logs.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+/// Digest authentication state shared by all requests of a client. The
+/// latest challenge is reused for subsequent requests, counting nonce uses,
+/// until the server sends a new one.
+#[derive(Debug)]
+pub(crate) struct DigestAuth {
+    credentials: Credentials,
+    session: Mutex<Option<Session>>,
+}
+
+#[derive(Debug)]
+struct Session {
+    challenge: Challenge,
+    /// Number of requests made with the challenge's nonce.
+    nc: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+    algorithm: Algorithm,
+    qop_auth: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Md5Sess,
+}
+
+impl DigestAuth {
+    pub(crate) fn new(credentials: Credentials) -> Self {
+        Self {
+            credentials,
+            session: Mutex::new(None),
+        }
+    }
+
+    /// Takes the challenge of a 401 response, replacing any previous one.
+    pub(crate) fn challenge(&self, headers: &HeaderMap) -> Result<()> {
+        let challenges: Vec<Challenge> = headers
+            .get_all(WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| Challenge::parse(value).ok())
+            .collect();
+
+        // epee offers MD5 and MD5-sess, prefer plain MD5.
+        let challenge = challenges
+            .iter()
+            .find(|c| c.algorithm == Algorithm::Md5)
+            .or_else(|| challenges.first())
+            .cloned()
+            .ok_or_else(|| anyhow!("no supported digest challenge in 401 response"))?;
+
+        *self.session.lock().expect("poisoned lock") = Some(Session { challenge, nc: 0 });
+
+        Ok(())
+    }
+
+    /// `Authorization` header value for a `method` request to `uri`, none if
+    /// no challenge has been received yet.
+    pub(crate) fn authorization(&self, method: &str, uri: &str) -> Option<String> {
+        let mut session = self.session.lock().expect("poisoned lock");
+        let session = session.as_mut()?;
+        session.nc += 1;
+
+        let cnonce = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        Some(
+            session
+                .challenge
+                .authorization(&self.credentials, method, uri, session.nc, &cnonce),
+        )
+    }
+}
+
+impl Challenge {
+    /// Parses a `WWW-Authenticate` header value, e.g. `Digest
+    /// qop="auth",algorithm=MD5,realm="monero-rpc",nonce="..."`.
+    fn parse(header: &str) -> Result<Self> {
+        let header = header.trim();
+        let params = match header.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("digest ") => &header[7..],
+            _ => bail!("not a digest challenge: {}", header),
+        };
+
+        let mut realm = None;
+        let mut nonce = None;
+        let mut opaque = None;
+        let mut algorithm = Algorithm::Md5;
+        let mut qop_auth = false;
+
+        for (key, value) in parse_params(params)? {
+            match key.to_ascii_lowercase().as_str() {
+                "realm" => realm = Some(value),
+                "nonce" => nonce = Some(value),
+                "opaque" => opaque = Some(value),
+                "algorithm" => {
+                    algorithm = match value.to_ascii_lowercase().as_str() {
+                        "md5" => Algorithm::Md5,
+                        "md5-sess" => Algorithm::Md5Sess,
+                        _ => bail!("unsupported digest algorithm {}", value),
+                    }
+                }
+                "qop" => {
+                    qop_auth = value.split(',').any(|qop| qop.trim() == "auth");
+                    if !qop_auth {
+                        bail!("unsupported qop {}", value);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            realm: realm.ok_or_else(|| anyhow!("digest challenge without realm"))?,
+            nonce: nonce.ok_or_else(|| anyhow!("digest challenge without nonce"))?,
+            opaque,
+            algorithm,
+            qop_auth,
+        })
+    }
+
+    fn authorization(
+        &self,
+        credentials: &Credentials,
+        method: &str,
+        uri: &str,
+        nc: u32,
+        cnonce: &str,
+    ) -> String {
+        let nc = format!("{:08x}", nc);
+        let response = self.response(credentials, method, uri, &nc, cnonce);
+
+        let algorithm = match self.algorithm {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+        };
+        let mut header = format!(
+            "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm={}, response=\"{}\"",
+            credentials.username, self.realm, self.nonce, uri, algorithm, response
+        );
+        if self.qop_auth {
+            header.push_str(&format!(", qop=auth, nc={}, cnonce=\"{}\"", nc, cnonce));
+        }
+        if let Some(opaque) = &self.opaque {
+            header.push_str(&format!(", opaque=\"{}\"", opaque));
+        }
+
+        header
+    }
+
+    /// The `response` parameter of the `Authorization` header.
+    fn response(
+        &self,
+        credentials: &Credentials,
+        method: &str,
+        uri: &str,
+        nc: &str,
+        cnonce: &str,
+    ) -> String {
+        let mut ha1 = md5_hex(&format!(
+            "{}:{}:{}",
+            credentials.username, self.realm, credentials.password
+        ));
+        if self.algorithm == Algorithm::Md5Sess {
+            ha1 = md5_hex(&format!("{}:{}:{}", ha1, self.nonce, cnonce));
+        }
+        let ha2 = md5_hex(&format!("{}:{}", method, uri));
+
+        if self.qop_auth {
+            md5_hex(&format!(
+                "{}:{}:{}:{}:auth:{}",
+                ha1, self.nonce, nc, cnonce, ha2
+            ))
+        } else {
+            md5_hex(&format!("{}:{}:{}", ha1, self.nonce, ha2))
+        }
+    }
+}
+
+/// Checks the `Authorization` header of a `method` request against
+/// `credentials` and the `nonce` issued by the server, for the fake daemons.
+#[cfg(feature = "mock")]
+pub(crate) fn verify(credentials: &Credentials, method: &str, nonce: &str, header: &str) -> bool {
+    let params = match header.trim().get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("digest ") => &header.trim()[7..],
+        _ => return false,
+    };
+    let params: std::collections::HashMap<String, String> = match parse_params(params) {
+        Ok(params) => params.into_iter().collect(),
+        Err(_) => return false,
+    };
+    let param = |key: &str| params.get(key).map(String::as_str).unwrap_or_default();
+
+    let challenge = Challenge {
+        realm: param("realm").to_owned(),
+        nonce: nonce.to_owned(),
+        opaque: None,
+        algorithm: match param("algorithm") {
+            "MD5-sess" => Algorithm::Md5Sess,
+            _ => Algorithm::Md5,
+        },
+        qop_auth: param("qop") == "auth",
+    };
+
+    param("username") == credentials.username
+        && param("nonce") == nonce
+        && param("response")
+            == challenge.response(
+                credentials,
+                method,
+                param("uri"),
+                param("nc"),
+                param("cnonce"),
+            )
+}
+
+/// `WWW-Authenticate` header value of a challenge with `nonce`, for the fake
+/// daemons.
+#[cfg(feature = "mock")]
+pub(crate) fn challenge_header(nonce: &str) -> String {
+    format!(
+        "Digest qop=\"auth\",algorithm=MD5,realm=\"monero-rpc\",nonce=\"{}\",stale=false",
+        nonce
+    )
+}
+
+/// Parses comma separated `key=value` pairs where values may be quoted and
+/// quoted values may contain commas.
+fn parse_params(s: &str) -> Result<Vec<(String, String)>> {
+    let mut params = vec![];
+    let mut chars = s.chars().peekable();
+
+    loop {
+        while matches!(chars.peek(), Some(c) if *c == ',' || c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        let key = key.trim().to_owned();
+
+        let value = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut value = String::new();
+            loop {
+                match chars.next() {
+                    Some('\\') => value.extend(chars.next()),
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => bail!("unterminated quoted value for {}", key),
+                }
+            }
+            value
+        } else {
+            let value: String = chars.by_ref().take_while(|c| *c != ',').collect();
+            value.trim().to_owned()
+        };
+
+        params.push((key, value));
+    }
+
+    Ok(params)
+}
+
+fn md5_hex(s: &str) -> String {
+    format!("{:x}", md5::compute(s.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn computes_rfc_2617_example_response() {
+        let challenge = Challenge::parse(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .expect("failed to parse challenge");
+        let credentials = Credentials::new("Mufasa", "Circle Of Life");
+
+        let got = challenge.authorization(&credentials, "GET", "/dir/index.html", 1, "0a4f113b");
+
+        assert_that!(got).is_equal_to(
+            "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+             algorithm=MD5, response=\"6629fae49393a05397450978507c4ef1\", qop=auth, \
+             nc=00000001, cnonce=\"0a4f113b\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn prefers_md5_challenge_and_counts_nonce_uses() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            WWW_AUTHENTICATE,
+            "Digest qop=\"auth\",algorithm=MD5-sess,realm=\"monero-rpc\",nonce=\"abc\",stale=false"
+                .parse()
+                .expect("valid header"),
+        );
+        headers.append(
+            WWW_AUTHENTICATE,
+            "Digest qop=\"auth\",algorithm=MD5,realm=\"monero-rpc\",nonce=\"abc\",stale=false"
+                .parse()
+                .expect("valid header"),
+        );
+        let auth = DigestAuth::new(Credentials::new("user", "pass"));
+
+        assert!(auth.authorization("POST", "/json_rpc").is_none());
+        auth.challenge(&headers).expect("failed to take challenge");
+
+        let first = auth
+            .authorization("POST", "/json_rpc")
+            .expect("authorization after challenge");
+        let second = auth
+            .authorization("POST", "/json_rpc")
+            .expect("authorization after challenge");
+
+        assert!(first.contains("algorithm=MD5,"));
+        assert!(first.contains("nc=00000001"));
+        assert!(second.contains("nc=00000002"));
+
+        auth.challenge(&headers)
+            .expect("failed to take new challenge");
+        let third = auth
+            .authorization("POST", "/json_rpc")
+            .expect("authorization after challenge");
+        assert!(third.contains("nc=00000001"));
+    }
+
+    #[test]
+    fn rejects_non_digest_challenge() {
+        assert!(Challenge::parse("Basic realm=\"monero-rpc\"").is_err());
+    }
+}
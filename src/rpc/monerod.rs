@@ -1,11 +1,12 @@
 use crate::{
-    rpc::{Request, Response},
+    rpc::{self, digest::DigestAuth, ClientConfig, Credentials, Request, Response, Tls},
     BlockHeader,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 // #[cfg(not(test))]
 // use tracing::debug;
@@ -18,6 +19,8 @@ use std::eprintln as debug;
 pub struct Client {
     pub inner: reqwest::Client,
     pub url: Url,
+    auth: Option<Arc<DigestAuth>>,
+    config: ClientConfig,
 }
 
 impl Client {
@@ -26,12 +29,66 @@ impl Client {
         let url = format!("http://127.0.0.1:{}/json_rpc", port);
         let url = Url::parse(&url).expect("url is well formed");
 
-        Self {
-            inner: reqwest::Client::new(),
+        Client::new(url)
+    }
+
+    /// New monerod RPC client with `url` endpoint.
+    pub fn new(url: Url) -> Self {
+        Client::with_config(url, ClientConfig::default()).expect("default config is valid")
+    }
+
+    /// New local host monerod RPC client for a monerod started with
+    /// `--rpc-ssl`.
+    pub fn localhost_with_tls(port: u16, tls: Tls) -> Result<Self> {
+        let url = format!("https://localhost:{}/json_rpc", port);
+        let url = Url::parse(&url).expect("url is well formed");
+
+        Client::with_tls(url, tls)
+    }
+
+    /// New monerod RPC client with `https` `url` endpoint, verifying the
+    /// certificate according to `tls`.
+    pub fn with_tls(url: Url, tls: Tls) -> Result<Self> {
+        let config = ClientConfig {
+            tls: Some(tls),
+            ..ClientConfig::default()
+        };
+
+        Client::with_config(url, config)
+    }
+
+    /// New monerod RPC client with `url` endpoint and HTTP client settings
+    /// `config`.
+    pub fn with_config(url: Url, config: ClientConfig) -> Result<Self> {
+        let inner = config.build(&url)?;
+
+        Ok(Self {
+            inner,
             url,
+            auth: None,
+            config,
+        })
+    }
+
+    /// Authenticates requests with `credentials`, for a monerod started with
+    /// `--rpc-login`.
+    pub fn with_credentials(self, credentials: Credentials) -> Self {
+        Self {
+            auth: Some(Arc::new(DigestAuth::new(credentials))),
+            ..self
         }
     }
 
+    async fn post<T: Serialize>(&self, url: Url, body: &T) -> Result<String> {
+        rpc::post(&self.inner, self.auth.as_deref(), &self.config, url, body).await
+    }
+
+    /// Like `post()` but retries transient failures, for calls that only
+    /// read.
+    async fn post_idempotent<T: Serialize>(&self, url: Url, body: &T) -> Result<String> {
+        rpc::post_idempotent(&self.inner, self.auth.as_deref(), &self.config, url, body).await
+    }
+
     pub async fn generate_blocks(
         &self,
         amount_of_blocks: u32,
@@ -43,14 +100,7 @@ impl Client {
         };
         let request = Request::new("generateblocks", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post(self.url.clone(), &request).await?;
 
         debug!("generate blocks response: {}", response);
 
@@ -64,14 +114,7 @@ impl Client {
         let params = GetBlockHeaderByHeightParams { height };
         let request = Request::new("get_block_header_by_height", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!("get block header by height response: {}", response);
 
@@ -79,6 +122,123 @@ impl Client {
 
         Ok(res.result.block_header)
     }
+
+    /// Get the per byte fee estimate for transactions to be mined within the
+    /// next few blocks.
+    pub async fn get_fee_estimate(&self) -> Result<FeeEstimate> {
+        let request = Request::new("get_fee_estimate", "");
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get fee estimate response: {}", response);
+
+        let res: Response<FeeEstimate> = serde_json::from_str(&response)?;
+
+        Ok(res.result)
+    }
+
+    /// Get general information about the state of the node.
+    pub async fn get_info(&self) -> Result<GetInfo> {
+        let request = Request::new("get_info", "");
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get info response: {}", response);
+
+        let res: Response<GetInfo> = serde_json::from_str(&response)?;
+
+        Ok(res.result)
+    }
+
+    /// Get the node's peer connections.
+    pub async fn get_connections(&self) -> Result<Vec<Connection>> {
+        let request = Request::new("get_connections", "");
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get connections response: {}", response);
+
+        let res: Response<GetConnections> = serde_json::from_str(&response)?;
+
+        Ok(res.result.connections)
+    }
+
+    /// Ban (or unban if `ban` is false) peer `host` (IP address) for
+    /// `seconds`, disconnecting it if connected.
+    pub async fn set_ban(&self, host: &str, ban: bool, seconds: u32) -> Result<()> {
+        let params = SetBansParams {
+            bans: vec![Ban {
+                host: host.to_owned(),
+                ban,
+                seconds,
+            }],
+        };
+        let request = Request::new("set_bans", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("set bans response: {}", response);
+
+        let res: Response<Status> = serde_json::from_str(&response)?;
+        if res.result.status != "OK" {
+            bail!("failed to set ban: {}", res.result.status);
+        }
+
+        Ok(())
+    }
+
+    /// Pop `nblocks` blocks off the top of the chain, their transactions are
+    /// returned to the tx pool.
+    pub async fn pop_blocks(&self, nblocks: u32) -> Result<PopBlocks> {
+        // pop_blocks is not a JSON RPC method, it has its own endpoint.
+        let url = self.url.join("pop_blocks")?;
+        let params = PopBlocksParams { nblocks };
+
+        let response = self.post(url, &params).await?;
+
+        debug!("pop blocks response: {}", response);
+
+        let res: PopBlocks = serde_json::from_str(&response)?;
+
+        Ok(res)
+    }
+
+    /// Submit a transaction to the node, `tx_as_hex` is e.g.
+    /// `wallet::Transfer::tx_blob`.
+    pub async fn send_raw_transaction(&self, tx_as_hex: &str) -> Result<SendRawTransaction> {
+        // send_raw_transaction is not a JSON RPC method, it has its own
+        // endpoint.
+        let url = self.url.join("send_raw_transaction")?;
+        let params = SendRawTransactionParams {
+            tx_as_hex: tx_as_hex.to_owned(),
+            do_not_relay: false,
+        };
+
+        let response = self.post(url, &params).await?;
+
+        debug!("send raw transaction response: {}", response);
+
+        let res: SendRawTransaction = serde_json::from_str(&response)?;
+
+        Ok(res)
+    }
+
+    /// Look up transactions by hash in the chain and the tx pool.
+    pub async fn get_transactions(&self, tx_hashes: Vec<String>) -> Result<GetTransactions> {
+        // get_transactions is not a JSON RPC method, it has its own endpoint.
+        let url = self.url.join("get_transactions")?;
+        let params = GetTransactionsParams {
+            txs_hashes: tx_hashes,
+        };
+
+        let response = self.post_idempotent(url, &params).await?;
+
+        debug!("get transactions response: {}", response);
+
+        let res: GetTransactions = serde_json::from_str(&response)?;
+
+        Ok(res)
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -105,3 +265,113 @@ struct GetBlockHeaderByHeight {
     status: String,
     untrusted: bool,
 }
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FeeEstimate {
+    /// Fee per byte of transaction weight, in moneroj.
+    pub fee: u64,
+    /// Fees are rounded up to a multiple of this.
+    pub quantization_mask: u64,
+    pub status: String,
+    pub untrusted: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetInfo {
+    pub height: u32,
+    pub target_height: u32,
+    pub top_block_hash: String,
+    pub incoming_connections_count: u32,
+    pub outgoing_connections_count: u32,
+    pub tx_pool_size: u32,
+    pub offline: bool,
+    pub status: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct GetConnections {
+    // Omitted by monerod if there are no connections.
+    #[serde(default)]
+    connections: Vec<Connection>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Connection {
+    pub address: String,
+    pub host: String,
+    pub port: String,
+    pub peer_id: String,
+    pub incoming: bool,
+    pub height: u32,
+    pub state: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SetBansParams {
+    bans: Vec<Ban>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct Ban {
+    host: String,
+    ban: bool,
+    seconds: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Status {
+    status: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct PopBlocksParams {
+    nblocks: u32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PopBlocks {
+    pub height: u32,
+    pub status: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SendRawTransactionParams {
+    tx_as_hex: String,
+    do_not_relay: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SendRawTransaction {
+    pub status: String,
+    /// Why the transaction was rejected, if it was.
+    #[serde(default)]
+    pub reason: String,
+    pub double_spend: bool,
+    pub not_relayed: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct GetTransactionsParams {
+    txs_hashes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GetTransactions {
+    /// Transactions found, omitted by monerod if none were.
+    #[serde(default)]
+    pub txs: Vec<Transaction>,
+    /// Hashes of transactions not found.
+    #[serde(default)]
+    pub missed_tx: Vec<String>,
+    pub status: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Transaction {
+    pub tx_hash: String,
+    pub in_pool: bool,
+    /// Only meaningful if the transaction is not in the pool.
+    #[serde(default)]
+    pub block_height: u32,
+    pub double_spend_seen: bool,
+}
@@ -0,0 +1,227 @@
+//! TLS for RPC servers started with `--rpc-ssl`.
+//!
+//! Monero daemons usually run with self-signed certificates, so besides the
+//! system roots a client can trust a given CA certificate, pin the SHA-256
+//! fingerprint of the server certificate or, in tests, accept any
+//! certificate.
+//!
+//! Pinned clients use rustls with a verifier that checks the fingerprint in
+//! the handshake of every connection, all other clients use native-tls.
+
+use anyhow::{anyhow, bail, Result};
+use reqwest::Url;
+use rustls::{
+    Certificate as RustlsCertificate, RootCertStore, ServerCertVerified, ServerCertVerifier,
+    TLSError,
+};
+use sha2::{Digest, Sha256};
+use std::{fmt, fs, path::Path, sync::Arc};
+use webpki::DNSNameRef;
+
+/// File name of the certificate written by `Certificate::write_to()`.
+pub(crate) const CERTIFICATE_FILE: &str = "rpc.crt";
+
+/// File name of the private key written by `Certificate::write_to()`.
+pub(crate) const PRIVATE_KEY_FILE: &str = "rpc.key";
+
+/// How a client verifies the certificate of an `https` RPC endpoint.
+#[derive(Clone, Debug)]
+pub enum Tls {
+    /// Trust certificates signed by this PEM encoded certificate, e.g. the
+    /// self-signed certificate of the daemon, in addition to the system
+    /// roots.
+    CaCertificate(String),
+    /// Accept only the certificate with this SHA-256 fingerprint, as hex
+    /// with or without colons like `--rpc-ssl-allowed-fingerprints`.
+    /// Hostname and expiry are not checked. The url must name the host, e.g.
+    /// `localhost`, as rustls cannot connect to an IP address.
+    Fingerprint(String),
+    /// Accept any certificate, for tests only.
+    AcceptAnyCert,
+}
+
+/// A PEM encoded certificate and its private key, as passed to
+/// `--rpc-ssl-certificate` and `--rpc-ssl-private-key`.
+#[derive(Clone)]
+pub struct Certificate {
+    pub certificate: String,
+    pub private_key: String,
+}
+
+impl Certificate {
+    /// Generates a self-signed certificate valid for `localhost` and
+    /// `127.0.0.1`.
+    pub fn generate() -> Result<Self> {
+        let mut params = rcgen::CertificateParams::new(vec!["localhost".to_owned()]);
+        params
+            .subject_alt_names
+            .push(rcgen::SanType::IpAddress([127, 0, 0, 1].into()));
+        let certificate = rcgen::Certificate::from_params(params)?;
+
+        Ok(Self {
+            certificate: certificate.serialize_pem()?,
+            private_key: certificate.serialize_private_key_pem(),
+        })
+    }
+
+    /// SHA-256 fingerprint of the certificate as colon separated hex, the
+    /// format monerod logs and accepts in `--rpc-ssl-allowed-fingerprints`.
+    pub fn fingerprint(&self) -> Result<String> {
+        let certificate = native_tls::Certificate::from_pem(self.certificate.as_bytes())?;
+        Ok(fingerprint(&certificate.to_der()?))
+    }
+
+    /// Writes the certificate and the private key to `dir`.
+    pub(crate) fn write_to(&self, dir: &Path) -> Result<()> {
+        fs::write(dir.join(CERTIFICATE_FILE), &self.certificate)?;
+        fs::write(dir.join(PRIVATE_KEY_FILE), &self.private_key)?;
+        Ok(())
+    }
+}
+
+/// Keeps the private key out of logs.
+impl fmt::Debug for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Certificate")
+            .field("certificate", &self.certificate)
+            .field("private_key", &"***")
+            .finish()
+    }
+}
+
+/// Configures `builder` to verify the certificate of `url` according to
+/// `tls`.
+pub(crate) fn configure(
+    builder: reqwest::ClientBuilder,
+    url: &Url,
+    tls: &Tls,
+) -> Result<reqwest::ClientBuilder> {
+    if url.scheme() != "https" {
+        bail!("TLS requires an https url, got {}", url);
+    }
+
+    match tls {
+        Tls::CaCertificate(pem) => {
+            let certificate = reqwest::Certificate::from_pem(pem.as_bytes())?;
+            Ok(builder.add_root_certificate(certificate))
+        }
+        Tls::Fingerprint(fingerprint) => {
+            let host = url
+                .host_str()
+                .ok_or_else(|| anyhow!("url has no host: {}", url))?;
+            if DNSNameRef::try_from_ascii_str(host).is_err() {
+                bail!("certificate pinning requires a host name, got {}", url);
+            }
+
+            let pin = Pin {
+                fingerprint: normalize_fingerprint(fingerprint)?,
+            };
+            let mut config = rustls::ClientConfig::new();
+            config.dangerous().set_certificate_verifier(Arc::new(pin));
+            Ok(builder.use_preconfigured_tls(config))
+        }
+        Tls::AcceptAnyCert => Ok(builder.danger_accept_invalid_certs(true)),
+    }
+}
+
+/// Accepts only the server certificate with the pinned fingerprint. rustls
+/// still checks that the server holds the certificate's private key.
+struct Pin {
+    fingerprint: String,
+}
+
+impl ServerCertVerifier for Pin {
+    fn verify_server_cert(
+        &self,
+        _: &RootCertStore,
+        presented_certs: &[RustlsCertificate],
+        _: DNSNameRef<'_>,
+        _: &[u8],
+    ) -> Result<ServerCertVerified, TLSError> {
+        let certificate = presented_certs
+            .first()
+            .ok_or(TLSError::NoCertificatesPresented)?;
+
+        let got = fingerprint(&certificate.0);
+        if got != self.fingerprint {
+            return Err(TLSError::General(format!(
+                "certificate has fingerprint {}, expected {}",
+                got, self.fingerprint
+            )));
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Brings a SHA-256 fingerprint given as hex, with or without colons, into
+/// the format of `fingerprint()`.
+fn normalize_fingerprint(fingerprint: &str) -> Result<String> {
+    let hex: String = fingerprint.chars().filter(|c| *c != ':').collect();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("not a SHA-256 fingerprint: {}", fingerprint);
+    }
+
+    let bytes: Vec<String> = hex
+        .to_ascii_uppercase()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).into_owned())
+        .collect();
+
+    Ok(bytes.join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spectral::prelude::*;
+
+    #[test]
+    fn normalizes_fingerprints_with_and_without_colons() {
+        let plain = "ab".repeat(32);
+        let colons = ["AB"; 32].join(":");
+
+        assert_that!(normalize_fingerprint(&plain).unwrap()).is_equal_to(colons.clone());
+        assert_that!(normalize_fingerprint(&colons).unwrap()).is_equal_to(colons);
+        assert_that!(normalize_fingerprint("ab:cd")).is_err();
+        assert_that!(normalize_fingerprint(&"zz".repeat(32))).is_err();
+    }
+
+    #[test]
+    fn fingerprint_of_generated_certificate_is_stable() {
+        let certificate = Certificate::generate().unwrap();
+
+        let fingerprint = certificate.fingerprint().unwrap();
+
+        assert_that!(normalize_fingerprint(&fingerprint).unwrap()).is_equal_to(fingerprint.clone());
+        assert_that!(certificate.fingerprint().unwrap()).is_equal_to(fingerprint);
+    }
+
+    #[test]
+    fn requires_https() {
+        let url = Url::parse("http://127.0.0.1:18081/json_rpc").unwrap();
+
+        let configured = configure(reqwest::Client::builder(), &url, &Tls::AcceptAnyCert);
+
+        assert_that!(configured).is_err();
+    }
+
+    #[test]
+    fn pinning_requires_a_host_name() {
+        let url = Url::parse("https://127.0.0.1:18081/json_rpc").unwrap();
+        let pinned = Tls::Fingerprint("ab".repeat(32));
+
+        let configured = configure(reqwest::Client::builder(), &url, &pinned);
+
+        assert_that!(configured).is_err();
+    }
+}
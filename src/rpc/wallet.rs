@@ -1,17 +1,20 @@
-use crate::rpc::{Request, Response};
+use crate::rpc::{self, digest::DigestAuth, ClientConfig, Credentials, Request, Response, Tls};
 
 use anyhow::Result;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 // TODO: Either use println! directly or import tracing also?
 use std::println as debug;
 
 /// JSON RPC client for monero-wallet-rpc.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Client {
     pub inner: reqwest::Client,
     pub url: Url,
+    auth: Option<Arc<DigestAuth>>,
+    config: ClientConfig,
 }
 
 impl Client {
@@ -25,25 +28,75 @@ impl Client {
 
     /// Constructs a monero-wallet-rpc client with `url` endpoint.
     pub fn new(url: Url) -> Self {
-        Self {
-            inner: reqwest::Client::new(),
+        Client::with_config(url, ClientConfig::default()).expect("default config is valid")
+    }
+
+    /// Constructs a monero-wallet-rpc client with localhost endpoint for a
+    /// monero-wallet-rpc started with `--rpc-ssl`.
+    pub fn localhost_with_tls(port: u16, tls: Tls) -> Result<Self> {
+        let url = format!("https://localhost:{}/json_rpc", port);
+        let url = Url::parse(&url).expect("url is well formed");
+
+        Client::with_tls(url, tls)
+    }
+
+    /// Constructs a monero-wallet-rpc client with `https` `url` endpoint,
+    /// verifying the certificate according to `tls`.
+    pub fn with_tls(url: Url, tls: Tls) -> Result<Self> {
+        let config = ClientConfig {
+            tls: Some(tls),
+            ..ClientConfig::default()
+        };
+
+        Client::with_config(url, config)
+    }
+
+    /// Constructs a monero-wallet-rpc client with `url` endpoint and HTTP
+    /// client settings `config`.
+    pub fn with_config(url: Url, config: ClientConfig) -> Result<Self> {
+        let inner = config.build(&url)?;
+
+        Ok(Self {
+            inner,
             url,
+            auth: None,
+            config,
+        })
+    }
+
+    /// Authenticates requests with `credentials`, for a monero-wallet-rpc
+    /// started with `--rpc-login`.
+    pub fn with_credentials(self, credentials: Credentials) -> Self {
+        Self {
+            auth: Some(Arc::new(DigestAuth::new(credentials))),
+            ..self
         }
     }
 
-    /// Get addresses for account by index.
-    pub async fn get_address(&self, account_index: u32) -> Result<GetAddress> {
-        let params = GetAddressParams { account_index };
+    async fn post<T: Serialize>(&self, url: Url, body: &T) -> Result<String> {
+        rpc::post(&self.inner, self.auth.as_deref(), &self.config, url, body).await
+    }
+
+    /// Like `post()` but retries transient failures, for calls that only
+    /// read.
+    async fn post_idempotent<T: Serialize>(&self, url: Url, body: &T) -> Result<String> {
+        rpc::post_idempotent(&self.inner, self.auth.as_deref(), &self.config, url, body).await
+    }
+
+    /// Get addresses for account by index, the subaddresses with
+    /// `address_indices` or all subaddresses if none.
+    pub async fn get_address(
+        &self,
+        account_index: u32,
+        address_indices: Option<Vec<u32>>,
+    ) -> Result<GetAddress> {
+        let params = GetAddressParams {
+            account_index,
+            address_index: address_indices,
+        };
         let request = Request::new("get_address", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!("get address RPC response: {}", response);
 
@@ -58,14 +111,7 @@ impl Client {
         };
         let request = Request::new("get_balance", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!(
             "get balance of account index {} RPC response: {}",
@@ -85,14 +131,7 @@ impl Client {
         };
         let request = Request::new("create_account", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post(self.url.clone(), &request).await?;
 
         debug!("create account RPC response: {}", response);
 
@@ -100,6 +139,208 @@ impl Client {
         Ok(r.result)
     }
 
+    /// Create `count` new subaddresses in account by index.
+    pub async fn create_address(
+        &self,
+        account_index: u32,
+        label: &str,
+        count: u32,
+    ) -> Result<CreateAddress> {
+        let params = CreateAddressParams {
+            account_index,
+            label: label.to_owned(),
+            count,
+        };
+        let request = Request::new("create_address", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("create address RPC response: {}", response);
+
+        let r: Response<CreateAddress> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Get the account and subaddress index of a wallet `address`.
+    pub async fn get_address_index(&self, address: &str) -> Result<SubAddressIndex> {
+        let params = AddressParams {
+            address: address.to_owned(),
+        };
+        let request = Request::new("get_address_index", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get address index RPC response: {}", response);
+
+        let r: Response<GetAddressIndex> = serde_json::from_str(&response)?;
+        Ok(r.result.index)
+    }
+
+    /// Set the label of the subaddress at `index`.
+    pub async fn label_address(&self, index: SubAddressIndex, label: &str) -> Result<()> {
+        let params = LabelAddressParams {
+            index,
+            label: label.to_owned(),
+        };
+        let request = Request::new("label_address", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("label address RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Set the label of account by index.
+    pub async fn label_account(&self, account_index: u32, label: &str) -> Result<()> {
+        let params = LabelAccountParams {
+            account_index,
+            label: label.to_owned(),
+        };
+        let request = Request::new("label_account", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("label account RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Tag `accounts` with `tag`, see `get_accounts()` for filtering by tag.
+    pub async fn tag_accounts(&self, tag: &str, accounts: Vec<u32>) -> Result<()> {
+        let params = TagAccountsParams {
+            tag: tag.to_owned(),
+            accounts,
+        };
+        let request = Request::new("tag_accounts", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("tag accounts RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Remove the tag from `accounts`.
+    pub async fn untag_accounts(&self, accounts: Vec<u32>) -> Result<()> {
+        let params = UntagAccountsParams { accounts };
+        let request = Request::new("untag_accounts", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("untag accounts RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Set the description of an account `tag`.
+    pub async fn set_account_tag_description(&self, tag: &str, description: &str) -> Result<()> {
+        let params = SetAccountTagDescriptionParams {
+            tag: tag.to_owned(),
+            description: description.to_owned(),
+        };
+        let request = Request::new("set_account_tag_description", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("set account tag description RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Make an integrated address from `standard_address` (the primary
+    /// address if empty) and `payment_id` (random if empty).
+    pub async fn make_integrated_address(
+        &self,
+        standard_address: &str,
+        payment_id: &str,
+    ) -> Result<IntegratedAddress> {
+        let params = MakeIntegratedAddressParams {
+            standard_address: standard_address.to_owned(),
+            payment_id: payment_id.to_owned(),
+        };
+        let request = Request::new("make_integrated_address", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("make integrated address RPC response: {}", response);
+
+        let r: Response<IntegratedAddress> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Split an integrated address into its standard address and payment id.
+    pub async fn split_integrated_address(
+        &self,
+        integrated_address: &str,
+    ) -> Result<SplitIntegratedAddress> {
+        let params = SplitIntegratedAddressParams {
+            integrated_address: integrated_address.to_owned(),
+        };
+        let request = Request::new("split_integrated_address", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("split integrated address RPC response: {}", response);
+
+        let r: Response<SplitIntegratedAddress> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Validate `address`, only addresses on the wallet's network type are
+    /// valid unless `any_net_type` is set.
+    pub async fn validate_address(
+        &self,
+        address: &str,
+        any_net_type: bool,
+    ) -> Result<ValidateAddress> {
+        let params = ValidateAddressParams {
+            address: address.to_owned(),
+            any_net_type,
+            allow_openalias: false,
+        };
+        let request = Request::new("validate_address", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("validate address RPC response: {}", response);
+
+        let r: Response<ValidateAddress> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Make a `monero:` payment URI.
+    pub async fn make_uri(&self, uri: &PaymentUri) -> Result<String> {
+        let request = Request::new("make_uri", uri);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("make uri RPC response: {}", response);
+
+        let r: Response<MakeUri> = serde_json::from_str(&response)?;
+        Ok(r.result.uri)
+    }
+
+    /// Parse a `monero:` payment URI.
+    pub async fn parse_uri(&self, uri: &str) -> Result<PaymentUri> {
+        let params = MakeUri {
+            uri: uri.to_owned(),
+        };
+        let request = Request::new("parse_uri", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("parse uri RPC response: {}", response);
+
+        let r: Response<ParseUri> = serde_json::from_str(&response)?;
+        Ok(r.result.uri)
+    }
+
     /// Get accounts, filtered by tag ("" for no filtering).
     pub async fn get_accounts(&self, tag: &str) -> Result<GetAccounts> {
         let params = TagParams {
@@ -107,14 +348,7 @@ impl Client {
         };
         let request = Request::new("get_accounts", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!("get accounts RPC response: {}", response);
 
@@ -131,20 +365,41 @@ impl Client {
         };
         let request = Request::new("create_wallet", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post(self.url.clone(), &request).await?;
 
         debug!("create wallet RPC response: {}", response);
 
         Ok(())
     }
 
+    /// Opens the wallet `filename`, closing the currently open wallet.
+    pub async fn open_wallet(&self, filename: &str) -> Result<()> {
+        let params = OpenWalletParams {
+            filename: filename.to_owned(),
+            password: String::new(),
+        };
+        let request = Request::new("open_wallet", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("open wallet RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Closes the currently open wallet.
+    pub async fn close_wallet(&self) -> Result<()> {
+        let request = Request::new("close_wallet", "");
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("close wallet RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
     /// Transfers `amount` moneroj from `account_index` to `address`.
     pub async fn transfer(
         &self,
@@ -159,27 +414,50 @@ impl Client {
         self.multi_transfer(account_index, dest).await
     }
 
+    /// Transfers `amount` moneroj from `account_index` to `address`, paying
+    /// fees according to `priority`.
+    pub async fn transfer_with_priority(
+        &self,
+        account_index: u32,
+        amount: u64,
+        address: &str,
+        priority: Priority,
+    ) -> Result<Transfer> {
+        let dest = vec![Destination {
+            amount,
+            address: address.to_owned(),
+        }];
+        self.multi_transfer_with_priority(account_index, dest, priority)
+            .await
+    }
+
     /// Transfers moneroj from `account_index` to `destinations`.
     pub async fn multi_transfer(
         &self,
         account_index: u32,
         destinations: Vec<Destination>,
+    ) -> Result<Transfer> {
+        self.multi_transfer_with_priority(account_index, destinations, Priority::Default)
+            .await
+    }
+
+    /// Transfers moneroj from `account_index` to `destinations`, paying fees
+    /// according to `priority`.
+    pub async fn multi_transfer_with_priority(
+        &self,
+        account_index: u32,
+        destinations: Vec<Destination>,
+        priority: Priority,
     ) -> Result<Transfer> {
         let params = TransferParams {
             account_index,
             destinations,
             get_tx_key: true,
+            priority: priority as u32,
         };
         let request = Request::new("transfer", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post(self.url.clone(), &request).await?;
 
         debug!("transfer RPC response: {}", response);
 
@@ -187,18 +465,137 @@ impl Client {
         Ok(r.result)
     }
 
+    /// Get outputs received by account by index, filtered by `transfer_type`.
+    pub async fn incoming_transfers(
+        &self,
+        transfer_type: TransferType,
+        account_index: u32,
+    ) -> Result<Vec<IncomingTransfer>> {
+        let params = IncomingTransfersParams {
+            transfer_type,
+            account_index,
+        };
+        let request = Request::new("incoming_transfers", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("incoming transfers RPC response: {}", response);
+
+        let r: Response<IncomingTransfers> = serde_json::from_str(&response)?;
+        Ok(r.result.transfers)
+    }
+
+    /// Sends the single output with `key_image` to `address`.
+    pub async fn sweep_single(&self, key_image: &str, address: &str) -> Result<Transfer> {
+        self.sweep_single_with_relay(key_image, address, true).await
+    }
+
+    /// Creates, but does not relay, a transaction sending the single output
+    /// with `key_image` to `address`. The output is not marked as spent so
+    /// this can be used to create conflicting transactions,
+    /// `Transfer::tx_blob` holds the transaction.
+    pub async fn create_sweep_single(&self, key_image: &str, address: &str) -> Result<Transfer> {
+        self.sweep_single_with_relay(key_image, address, false)
+            .await
+    }
+
+    async fn sweep_single_with_relay(
+        &self,
+        key_image: &str,
+        address: &str,
+        relay: bool,
+    ) -> Result<Transfer> {
+        let params = SweepSingleParams {
+            address: address.to_owned(),
+            key_image: key_image.to_owned(),
+            get_tx_key: true,
+            do_not_relay: !relay,
+            get_tx_hex: !relay,
+        };
+        let request = Request::new("sweep_single", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("sweep single RPC response: {}", response);
+
+        let r: Response<Transfer> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Freeze the output with `key_image` so it is not used in transfers.
+    pub async fn freeze(&self, key_image: &str) -> Result<()> {
+        let params = KeyImageParams {
+            key_image: key_image.to_owned(),
+        };
+        let request = Request::new("freeze", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("freeze RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Thaw a frozen output with `key_image`.
+    pub async fn thaw(&self, key_image: &str) -> Result<()> {
+        let params = KeyImageParams {
+            key_image: key_image.to_owned(),
+        };
+        let request = Request::new("thaw", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("thaw RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Check if the output with `key_image` is frozen.
+    pub async fn frozen(&self, key_image: &str) -> Result<bool> {
+        let params = KeyImageParams {
+            key_image: key_image.to_owned(),
+        };
+        let request = Request::new("frozen", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("frozen RPC response: {}", response);
+
+        let r: Response<Frozen> = serde_json::from_str(&response)?;
+        Ok(r.result.frozen)
+    }
+
+    /// Estimate the size and weight of a transaction with `n_inputs` and
+    /// `n_outputs`.
+    pub async fn estimate_tx_size_and_weight(
+        &self,
+        n_inputs: u32,
+        n_outputs: u32,
+        ring_size: u32,
+    ) -> Result<TxSizeAndWeight> {
+        let params = EstimateTxSizeAndWeightParams {
+            n_inputs,
+            n_outputs,
+            ring_size,
+            rct: true,
+        };
+        let request = Request::new("estimate_tx_size_and_weight", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("estimate tx size and weight RPC response: {}", response);
+
+        let r: Response<TxSizeAndWeight> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
     /// Get wallet block height, this might be behind monerod height.
-    pub(crate) async fn block_height(&self) -> Result<BlockHeight> {
+    pub async fn block_height(&self) -> Result<BlockHeight> {
         let request = Request::new("get_height", "");
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!("wallet height RPC response: {}", response);
 
@@ -206,6 +603,88 @@ impl Client {
         Ok(r.result)
     }
 
+    /// Refresh the open wallet, from `start_height` if some, returns once the
+    /// wallet has synced with the daemon.
+    pub async fn refresh(&self, start_height: Option<u32>) -> Result<Refreshed> {
+        let params = RefreshParams { start_height };
+        let request = Request::new("refresh", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("refresh RPC response: {}", response);
+
+        let r: Response<Refreshed> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Enable or disable background refreshing of the open wallet, every
+    /// `period` seconds if some.
+    pub async fn auto_refresh(&self, enable: bool, period: Option<u32>) -> Result<()> {
+        let params = AutoRefreshParams { enable, period };
+        let request = Request::new("auto_refresh", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("auto refresh RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Rescan the blockchain from scratch, a `hard` rescan also drops
+    /// spent/unspent status and transaction notes.
+    pub async fn rescan_blockchain(&self, hard: bool) -> Result<()> {
+        let params = RescanBlockchainParams { hard };
+        let request = Request::new("rescan_blockchain", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("rescan blockchain RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Rescan the blockchain for spent outputs.
+    pub async fn rescan_spent(&self) -> Result<()> {
+        let request = Request::new("rescan_spent", "");
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("rescan spent RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Connect the wallet to the daemon at `address` (host:port).
+    pub async fn set_daemon(&self, address: &str, trusted: bool) -> Result<()> {
+        let params = SetDaemonParams {
+            address: address.to_owned(),
+            trusted,
+        };
+        let request = Request::new("set_daemon", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("set daemon RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Get the monero-wallet-rpc RPC version.
+    pub async fn get_version(&self) -> Result<Version> {
+        let request = Request::new("get_version", "");
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get version RPC response: {}", response);
+
+        let r: Response<Version> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
     /// Check a transaction in the blockchain with its secret key.
     pub async fn check_tx_key(
         &self,
@@ -220,40 +699,675 @@ impl Client {
         };
         let request = Request::new("check_tx_key", params);
 
-        let response = self
-            .inner
-            .post(self.url.clone())
-            .json(&request)
-            .send()
-            .await?
-            .text()
-            .await?;
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
         debug!("transfer RPC response: {}", response);
 
         let r: Response<CheckTxKey> = serde_json::from_str(&response)?;
         Ok(r.result)
     }
-}
 
-#[derive(Serialize, Debug, Clone)]
-struct GetAddressParams {
-    account_index: u32,
-}
+    /// Check if the open wallet is a multisig wallet.
+    pub async fn is_multisig(&self) -> Result<IsMultisig> {
+        let request = Request::new("is_multisig", "");
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct GetAddress {
-    pub address: String,
-}
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
 
-#[derive(Serialize, Debug, Clone)]
-struct GetBalanceParams {
-    account_index: u32,
-}
+        debug!("is multisig RPC response: {}", response);
 
-#[derive(Deserialize, Debug, Clone)]
-struct GetBalance {
-    balance: u64,
+        let r: Response<IsMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Prepare the open wallet for multisig, returns the multisig info to
+    /// share with the other participants.
+    pub async fn prepare_multisig(&self) -> Result<String> {
+        let request = Request::new("prepare_multisig", "");
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("prepare multisig RPC response: {}", response);
+
+        let r: Response<PrepareMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result.multisig_info)
+    }
+
+    /// Make a `threshold`-of-N multisig wallet from the other participants'
+    /// `multisig_info`.
+    pub async fn make_multisig(
+        &self,
+        multisig_info: Vec<String>,
+        threshold: u32,
+    ) -> Result<MakeMultisig> {
+        let params = MakeMultisigParams {
+            multisig_info,
+            threshold,
+            password: String::new(),
+        };
+        let request = Request::new("make_multisig", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("make multisig RPC response: {}", response);
+
+        let r: Response<MakeMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Perform a round of multisig key exchange, required for M-of-N wallets
+    /// where M < N.
+    pub async fn exchange_multisig_keys(
+        &self,
+        multisig_info: Vec<String>,
+    ) -> Result<ExchangeMultisigKeys> {
+        let params = MultisigInfoParams {
+            multisig_info,
+            password: String::new(),
+        };
+        let request = Request::new("exchange_multisig_keys", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("exchange multisig keys RPC response: {}", response);
+
+        let r: Response<ExchangeMultisigKeys> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Finalize an (N-1)-of-N multisig wallet, returns the multisig address.
+    pub async fn finalize_multisig(&self, multisig_info: Vec<String>) -> Result<String> {
+        let params = MultisigInfoParams {
+            multisig_info,
+            password: String::new(),
+        };
+        let request = Request::new("finalize_multisig", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("finalize multisig RPC response: {}", response);
+
+        let r: Response<FinalizeMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result.address)
+    }
+
+    /// Export multisig info for the other participants, required before
+    /// spending from a multisig wallet.
+    pub async fn export_multisig_info(&self) -> Result<String> {
+        let request = Request::new("export_multisig_info", "");
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("export multisig info RPC response: {}", response);
+
+        let r: Response<ExportMultisigInfo> = serde_json::from_str(&response)?;
+        Ok(r.result.info)
+    }
+
+    /// Import multisig info exported by the other participants, returns the
+    /// number of outputs signed with the imported info.
+    pub async fn import_multisig_info(&self, info: Vec<String>) -> Result<u32> {
+        let params = ImportMultisigInfoParams { info };
+        let request = Request::new("import_multisig_info", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("import multisig info RPC response: {}", response);
+
+        let r: Response<ImportMultisigInfo> = serde_json::from_str(&response)?;
+        Ok(r.result.n_outputs)
+    }
+
+    /// Sign a multisig transaction set, e.g. `Transfer::multisig_txset`.
+    pub async fn sign_multisig(&self, tx_data_hex: &str) -> Result<SignMultisig> {
+        let params = TxDataHexParams {
+            tx_data_hex: tx_data_hex.to_owned(),
+        };
+        let request = Request::new("sign_multisig", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("sign multisig RPC response: {}", response);
+
+        let r: Response<SignMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Submit a fully signed multisig transaction set, returns the
+    /// transaction hashes.
+    pub async fn submit_multisig(&self, tx_data_hex: &str) -> Result<Vec<String>> {
+        let params = TxDataHexParams {
+            tx_data_hex: tx_data_hex.to_owned(),
+        };
+        let request = Request::new("submit_multisig", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("submit multisig RPC response: {}", response);
+
+        let r: Response<SubmitMultisig> = serde_json::from_str(&response)?;
+        Ok(r.result.tx_hash_list)
+    }
+
+    /// Creates a wallet from keys and opens it, omitting `spend_key` creates a
+    /// view-only wallet.
+    pub async fn generate_from_keys(
+        &self,
+        filename: &str,
+        address: &str,
+        spend_key: Option<&str>,
+        view_key: &str,
+        restore_height: u32,
+    ) -> Result<GenerateFromKeys> {
+        let params = GenerateFromKeysParams {
+            restore_height,
+            filename: filename.to_owned(),
+            address: address.to_owned(),
+            spendkey: spend_key.map(ToOwned::to_owned),
+            viewkey: view_key.to_owned(),
+            password: String::new(),
+        };
+        let request = Request::new("generate_from_keys", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("generate from keys RPC response: {}", response);
+
+        let r: Response<GenerateFromKeys> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Query the mnemonic seed or a private key of the open wallet.
+    pub async fn query_key(&self, key_type: KeyType) -> Result<String> {
+        let params = QueryKeyParams { key_type };
+        let request = Request::new("query_key", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("query key RPC response: {}", response);
+
+        let r: Response<QueryKey> = serde_json::from_str(&response)?;
+        Ok(r.result.key)
+    }
+
+    /// Sign `data` with the spend key of the subaddress at `index`,
+    /// monero-wallet-rpc v0.16 cannot sign with the view key.
+    pub async fn sign(&self, data: &str, index: SubAddressIndex) -> Result<String> {
+        let params = SignParams {
+            data: data.to_owned(),
+            account_index: index.major,
+            address_index: index.minor,
+        };
+        let request = Request::new("sign", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("sign RPC response: {}", response);
+
+        let r: Response<Sign> = serde_json::from_str(&response)?;
+        Ok(r.result.signature)
+    }
+
+    /// Verify a `signature` of `data` by `address`.
+    pub async fn verify(&self, data: &str, address: &str, signature: &str) -> Result<Verify> {
+        let params = VerifyParams {
+            data: data.to_owned(),
+            address: address.to_owned(),
+            signature: signature.to_owned(),
+        };
+        let request = Request::new("verify", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("verify RPC response: {}", response);
+
+        let r: Response<Verify> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Get a custom attribute of the open wallet.
+    pub async fn get_attribute(&self, key: &str) -> Result<String> {
+        let params = GetAttributeParams {
+            key: key.to_owned(),
+        };
+        let request = Request::new("get_attribute", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get attribute RPC response: {}", response);
+
+        let r: Response<GetAttribute> = serde_json::from_str(&response)?;
+        Ok(r.result.value)
+    }
+
+    /// Set a custom attribute of the open wallet.
+    pub async fn set_attribute(&self, key: &str, value: &str) -> Result<()> {
+        let params = SetAttributeParams {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        };
+        let request = Request::new("set_attribute", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("set attribute RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Set notes for transactions, `tx_ids` and `notes` are matched by
+    /// position.
+    pub async fn set_tx_notes(&self, tx_ids: Vec<String>, notes: Vec<String>) -> Result<()> {
+        let params = SetTxNotesParams { tx_ids, notes };
+        let request = Request::new("set_tx_notes", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("set tx notes RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Get notes for transactions, in the same order as `tx_ids`.
+    pub async fn get_tx_notes(&self, tx_ids: Vec<String>) -> Result<Vec<String>> {
+        let params = GetTxNotesParams { tx_ids };
+        let request = Request::new("get_tx_notes", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get tx notes RPC response: {}", response);
+
+        let r: Response<GetTxNotes> = serde_json::from_str(&response)?;
+        Ok(r.result.notes)
+    }
+
+    /// Get address book entries by index (empty for all entries).
+    pub async fn get_address_book(&self, entries: Vec<u32>) -> Result<Vec<AddressBookEntry>> {
+        let params = GetAddressBookParams { entries };
+        let request = Request::new("get_address_book", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("get address book RPC response: {}", response);
+
+        let r: Response<GetAddressBook> = serde_json::from_str(&response)?;
+        Ok(r.result.entries)
+    }
+
+    /// Add an entry to the address book, returns the index of the new entry.
+    pub async fn add_address_book(&self, address: &str, description: &str) -> Result<u32> {
+        let params = AddAddressBookParams {
+            address: address.to_owned(),
+            description: description.to_owned(),
+        };
+        let request = Request::new("add_address_book", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("add address book RPC response: {}", response);
+
+        let r: Response<AddAddressBook> = serde_json::from_str(&response)?;
+        Ok(r.result.index)
+    }
+
+    /// Edit the address book entry at `index`, fields that are none are left
+    /// unchanged.
+    pub async fn edit_address_book(
+        &self,
+        index: u32,
+        address: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<()> {
+        let params = EditAddressBookParams {
+            index,
+            set_address: address.is_some(),
+            address: address.unwrap_or_default().to_owned(),
+            set_description: description.is_some(),
+            description: description.unwrap_or_default().to_owned(),
+        };
+        let request = Request::new("edit_address_book", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("edit address book RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Delete the address book entry at `index`.
+    pub async fn delete_address_book(&self, index: u32) -> Result<()> {
+        let params = DeleteAddressBookParams { index };
+        let request = Request::new("delete_address_book", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("delete address book RPC response: {}", response);
+
+        let _: Response<Empty> = serde_json::from_str(&response)?;
+        Ok(())
+    }
+
+    /// Describe an unsigned transaction set, e.g. `Transfer::unsigned_txset`.
+    pub async fn describe_transfer(
+        &self,
+        unsigned_txset: &str,
+    ) -> Result<Vec<TransferDescription>> {
+        let params = UnsignedTxsetParams {
+            unsigned_txset: unsigned_txset.to_owned(),
+        };
+        let request = Request::new("describe_transfer", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("describe transfer RPC response: {}", response);
+
+        let r: Response<DescribeTransfer> = serde_json::from_str(&response)?;
+        Ok(r.result.desc)
+    }
+
+    /// Sign an unsigned transaction set created by a view-only wallet.
+    pub async fn sign_transfer(&self, unsigned_txset: &str) -> Result<SignTransfer> {
+        let params = SignTransferParams {
+            unsigned_txset: unsigned_txset.to_owned(),
+            export_raw: false,
+        };
+        let request = Request::new("sign_transfer", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("sign transfer RPC response: {}", response);
+
+        let r: Response<SignTransfer> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Submit a signed transaction set, e.g. `SignTransfer::signed_txset`,
+    /// returns the transaction hashes.
+    pub async fn submit_transfer(&self, tx_data_hex: &str) -> Result<Vec<String>> {
+        let params = TxDataHexParams {
+            tx_data_hex: tx_data_hex.to_owned(),
+        };
+        let request = Request::new("submit_transfer", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("submit transfer RPC response: {}", response);
+
+        let r: Response<SubmitTransfer> = serde_json::from_str(&response)?;
+        Ok(r.result.tx_hash_list)
+    }
+
+    /// Export outputs of the open wallet, usually a view-only wallet, as hex.
+    pub async fn export_outputs(&self, all: bool) -> Result<String> {
+        let params = AllParams { all };
+        let request = Request::new("export_outputs", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("export outputs RPC response: {}", response);
+
+        let r: Response<ExportOutputs> = serde_json::from_str(&response)?;
+        Ok(r.result.outputs_data_hex)
+    }
+
+    /// Import outputs exported by `export_outputs()`, returns the number of
+    /// outputs imported.
+    pub async fn import_outputs(&self, outputs_data_hex: &str) -> Result<u32> {
+        let params = ImportOutputsParams {
+            outputs_data_hex: outputs_data_hex.to_owned(),
+        };
+        let request = Request::new("import_outputs", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("import outputs RPC response: {}", response);
+
+        let r: Response<ImportOutputs> = serde_json::from_str(&response)?;
+        Ok(r.result.num_imported)
+    }
+
+    /// Export signed key images of the open wallet, usually the signing
+    /// wallet.
+    pub async fn export_key_images(&self, all: bool) -> Result<ExportKeyImages> {
+        let params = AllParams { all };
+        let request = Request::new("export_key_images", params);
+
+        let response = self.post_idempotent(self.url.clone(), &request).await?;
+
+        debug!("export key images RPC response: {}", response);
+
+        let r: Response<ExportKeyImages> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+
+    /// Import signed key images, allows a view-only wallet to see spends.
+    pub async fn import_key_images(
+        &self,
+        offset: u32,
+        signed_key_images: Vec<SignedKeyImage>,
+    ) -> Result<ImportKeyImages> {
+        let params = ImportKeyImagesParams {
+            offset,
+            signed_key_images,
+        };
+        let request = Request::new("import_key_images", params);
+
+        let response = self.post(self.url.clone(), &request).await?;
+
+        debug!("import key images RPC response: {}", response);
+
+        let r: Response<ImportKeyImages> = serde_json::from_str(&response)?;
+        Ok(r.result)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GetAddressParams {
+    account_index: u32,
+    // All subaddresses of the account are returned if none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address_index: Option<Vec<u32>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetAddress {
+    pub address: String,
+    #[serde(default)]
+    pub addresses: Vec<SubAddress>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SubAddress {
+    pub address: String,
+    pub address_index: u32,
+    pub label: String,
+    pub used: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubAddressIndex {
+    /// Account index.
+    pub major: u32,
+    /// Subaddress index within the account.
+    pub minor: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct CreateAddressParams {
+    account_index: u32,
+    label: String,
+    count: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateAddress {
+    /// The first address created.
+    pub address: String,
+    pub address_index: u32,
+    /// All addresses created, empty if the wallet does not support `count`.
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub address_indices: Vec<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct AddressParams {
+    address: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct GetAddressIndex {
+    index: SubAddressIndex,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct LabelAddressParams {
+    index: SubAddressIndex,
+    label: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct LabelAccountParams {
+    account_index: u32,
+    label: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct TagAccountsParams {
+    tag: String,
+    accounts: Vec<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct UntagAccountsParams {
+    accounts: Vec<u32>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SetAccountTagDescriptionParams {
+    tag: String,
+    description: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MakeIntegratedAddressParams {
+    standard_address: String,
+    payment_id: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct IntegratedAddress {
+    pub integrated_address: String,
+    pub payment_id: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SplitIntegratedAddressParams {
+    integrated_address: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SplitIntegratedAddress {
+    pub is_subaddress: bool,
+    pub payment_id: String,
+    pub standard_address: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ValidateAddressParams {
+    address: String,
+    any_net_type: bool,
+    allow_openalias: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidateAddress {
+    pub valid: bool,
+    pub integrated: bool,
+    pub subaddress: bool,
+    pub nettype: String,
+    pub openalias_address: String,
+}
+
+/// A `monero:` payment URI, empty or zero fields are omitted from the URI.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaymentUri {
+    pub address: String,
+    /// Amount in moneroj, encoded as `tx_amount` in XMR.
+    #[serde(default)]
+    pub amount: u64,
+    #[serde(default)]
+    pub payment_id: String,
+    #[serde(default)]
+    pub recipient_name: String,
+    #[serde(default)]
+    pub tx_description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct MakeUri {
+    uri: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ParseUri {
+    uri: PaymentUri,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct RefreshParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_height: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Refreshed {
+    pub blocks_fetched: u32,
+    pub received_money: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct AutoRefreshParams {
+    enable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<u32>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct RescanBlockchainParams {
+    hard: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SetDaemonParams {
+    address: String,
+    trusted: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Version {
+    /// RPC version, major version in the upper 16 bits and minor version in
+    /// the lower 16 bits.
+    pub version: u32,
+    #[serde(default)]
+    pub release: bool,
+}
+
+/// Result of RPC calls that return nothing on success.
+#[derive(Deserialize, Debug, Clone, Copy)]
+struct Empty {}
+
+#[derive(Serialize, Debug, Clone)]
+struct GetBalanceParams {
+    account_index: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct GetBalance {
+    balance: u64,
     blocks_to_unlock: u32,
     multisig_import_needed: bool,
     time_to_unlock: u32,
@@ -299,6 +1413,12 @@ struct CreateWalletParams {
     language: String,
 }
 
+#[derive(Serialize, Debug, Clone)]
+struct OpenWalletParams {
+    filename: String,
+    password: String,
+}
+
 #[derive(Serialize, Debug, Clone)]
 struct TransferParams {
     // Transfer from this account.
@@ -307,6 +1427,20 @@ struct TransferParams {
     destinations: Vec<Destination>,
     // Return the transaction key after sending.
     get_tx_key: bool,
+    // Fee priority, see `Priority`.
+    priority: u32,
+}
+
+/// Transaction fee priority, higher priorities pay a higher fee multiplier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Let the wallet choose, this is `Unimportant` unless the tx pool is
+    /// backlogged.
+    Default = 0,
+    Unimportant = 1,
+    Normal = 2,
+    Elevated = 3,
+    Priority = 4,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -327,6 +1461,78 @@ pub struct Transfer {
     pub unsigned_txset: String,
 }
 
+#[derive(Serialize, Debug, Clone, Copy)]
+struct IncomingTransfersParams {
+    transfer_type: TransferType,
+    account_index: u32,
+}
+
+/// Filter for `Client::incoming_transfers()`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferType {
+    All,
+    /// Unspent outputs only.
+    Available,
+    /// Spent outputs only.
+    Unavailable,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct IncomingTransfers {
+    // Omitted by the wallet if there are no matching outputs.
+    #[serde(default)]
+    transfers: Vec<IncomingTransfer>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct IncomingTransfer {
+    pub amount: u64,
+    pub global_index: u64,
+    pub key_image: String,
+    pub spent: bool,
+    pub subaddr_index: SubAddressIndex,
+    pub tx_hash: String,
+    #[serde(default)]
+    pub frozen: bool,
+    #[serde(default)]
+    pub unlocked: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SweepSingleParams {
+    address: String,
+    key_image: String,
+    get_tx_key: bool,
+    do_not_relay: bool,
+    // Return the transaction blob, needed if it is not relayed.
+    get_tx_hex: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct KeyImageParams {
+    key_image: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct Frozen {
+    frozen: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct EstimateTxSizeAndWeightParams {
+    n_inputs: u32,
+    n_outputs: u32,
+    ring_size: u32,
+    rct: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct TxSizeAndWeight {
+    pub size: u64,
+    pub weight: u64,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize)]
 pub struct BlockHeight {
     pub height: u32,
@@ -346,3 +1552,314 @@ pub struct CheckTxKey {
     pub in_pool: bool,
     pub received: u64,
 }
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct IsMultisig {
+    pub multisig: bool,
+    pub ready: bool,
+    pub threshold: u32,
+    pub total: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PrepareMultisig {
+    multisig_info: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MakeMultisigParams {
+    multisig_info: Vec<String>,
+    threshold: u32,
+    password: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MakeMultisig {
+    pub address: String,
+    /// Empty unless another round of key exchange is required.
+    pub multisig_info: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MultisigInfoParams {
+    multisig_info: Vec<String>,
+    password: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ExchangeMultisigKeys {
+    pub address: String,
+    /// Empty unless another round of key exchange is required.
+    pub multisig_info: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct FinalizeMultisig {
+    address: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ExportMultisigInfo {
+    info: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ImportMultisigInfoParams {
+    info: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct ImportMultisigInfo {
+    n_outputs: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct TxDataHexParams {
+    tx_data_hex: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SignMultisig {
+    pub tx_data_hex: String,
+    pub tx_hash_list: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct SubmitMultisig {
+    tx_hash_list: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GenerateFromKeysParams {
+    restore_height: u32,
+    filename: String,
+    address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spendkey: Option<String>,
+    viewkey: String,
+    password: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GenerateFromKeys {
+    pub address: String,
+    pub info: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct QueryKeyParams {
+    key_type: KeyType,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    Mnemonic,
+    ViewKey,
+    SpendKey,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct QueryKey {
+    key: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SignParams {
+    data: String,
+    account_index: u32,
+    address_index: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Sign {
+    signature: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct VerifyParams {
+    data: String,
+    address: String,
+    signature: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Verify {
+    pub good: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GetAttributeParams {
+    key: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct GetAttribute {
+    value: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SetAttributeParams {
+    key: String,
+    value: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SetTxNotesParams {
+    #[serde(rename = "txids")]
+    tx_ids: Vec<String>,
+    notes: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GetTxNotesParams {
+    #[serde(rename = "txids")]
+    tx_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct GetTxNotes {
+    notes: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GetAddressBookParams {
+    entries: Vec<u32>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct GetAddressBook {
+    // Omitted by the wallet if the address book is empty.
+    #[serde(default)]
+    entries: Vec<AddressBookEntry>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddressBookEntry {
+    pub index: u32,
+    pub address: String,
+    pub description: String,
+    #[serde(default)]
+    pub payment_id: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct AddAddressBookParams {
+    address: String,
+    description: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct AddAddressBook {
+    index: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct EditAddressBookParams {
+    index: u32,
+    set_address: bool,
+    address: String,
+    set_description: bool,
+    description: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct DeleteAddressBookParams {
+    index: u32,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct UnsignedTxsetParams {
+    unsigned_txset: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct DescribeTransfer {
+    desc: Vec<TransferDescription>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TransferDescription {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub recipients: Vec<Recipient>,
+    pub change_address: String,
+    pub change_amount: u64,
+    pub fee: u64,
+    pub payment_id: String,
+    pub ring_size: u32,
+    pub unlock_time: u64,
+    pub dummy_outputs: u32,
+    pub extra: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Recipient {
+    pub address: String,
+    pub amount: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SignTransferParams {
+    unsigned_txset: String,
+    // Also return the raw transactions, we don't need them.
+    export_raw: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SignTransfer {
+    pub signed_txset: String,
+    pub tx_hash_list: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct SubmitTransfer {
+    tx_hash_list: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+struct AllParams {
+    all: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ExportOutputs {
+    outputs_data_hex: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ImportOutputsParams {
+    outputs_data_hex: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+struct ImportOutputs {
+    num_imported: u32,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ExportKeyImages {
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default)]
+    pub signed_key_images: Vec<SignedKeyImage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedKeyImage {
+    pub key_image: String,
+    pub signature: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ImportKeyImagesParams {
+    offset: u32,
+    signed_key_images: Vec<SignedKeyImage>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct ImportKeyImages {
+    pub height: u64,
+    pub spent: u64,
+    pub unspent: u64,
+}
@@ -0,0 +1,39 @@
+//! Local HTTP servers backing the fakes and the proxy.
+
+use futures::{channel::oneshot, future, Future};
+
+/// Handle to a server spawned on the tokio runtime, stops the server when
+/// dropped.
+#[derive(Debug)]
+pub(crate) struct Running {
+    /// Local port the server listens on.
+    pub port: u16,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Running {
+    /// Spawns `server`, which listens on local `port`.
+    pub(crate) fn spawn<F>(port: u16, server: F) -> Self
+    where
+        F: Future + Send + 'static,
+        F::Output: Send,
+    {
+        // Dropping the server on shutdown also drops open connections, a
+        // graceful shutdown would wait for stalled ones forever.
+        let (shutdown, stop) = oneshot::channel::<()>();
+        tokio::spawn(future::select(Box::pin(server), stop));
+
+        Self {
+            port,
+            shutdown: Some(shutdown),
+        }
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use futures::future;
+use monero_harness::{
+    mock::{monerod::Monerod, wallet::WalletRpc},
+    rpc::blocking::{monerod, wallet},
+};
+use spectral::prelude::*;
+use std::{sync::mpsc, thread};
+
+const ADDRESS: &str = "mock_address";
+
+/// Runs `start` and keeps the fake it returns serving on a runtime in a
+/// background thread, returns the fake's port. The thread lives until the
+/// test process exits.
+fn serve<T, F>(start: F) -> u16
+where
+    F: FnOnce() -> (T, u16) + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .expect("failed to start runtime");
+
+        runtime.block_on(async move {
+            let (_fake, port) = start();
+            tx.send(port).expect("test is waiting");
+            future::pending::<()>().await;
+        });
+    });
+
+    rx.recv().expect("failed to start fake")
+}
+
+fn start_monerod() -> u16 {
+    serve(|| {
+        let monerod = Monerod::start().expect("failed to start mock monerod");
+        let port = monerod.port;
+        (monerod, port)
+    })
+}
+
+fn start_wallet_rpc() -> u16 {
+    serve(|| {
+        let wallet_rpc = WalletRpc::start().expect("failed to start mock wallet-rpc");
+        let port = wallet_rpc.port;
+        (wallet_rpc, port)
+    })
+}
+
+#[test]
+fn monerod_client_without_runtime() {
+    let cli = monerod::Client::localhost(start_monerod());
+
+    let res = cli
+        .generate_blocks(3, ADDRESS)
+        .expect("failed to generate blocks");
+    let header = cli
+        .get_block_header_by_height(3)
+        .expect("failed to get header");
+
+    assert_that!(res.height).is_equal_to(3);
+    assert_that!(header.hash).is_equal_to(&res.blocks[2]);
+}
+
+#[test]
+fn wallet_client_without_runtime() {
+    let cli = wallet::Client::localhost(start_wallet_rpc());
+
+    cli.create_wallet("alice").expect("failed to create wallet");
+    let balance = cli.get_balance(0).expect("failed to get balance");
+
+    assert_that!(balance).is_equal_to(0);
+}
+
+#[test]
+fn cloned_wallet_client_shares_the_endpoint() {
+    let cli = wallet::Client::localhost(start_wallet_rpc());
+    let clone = cli.clone();
+
+    cli.create_wallet("alice").expect("failed to create wallet");
+    let address = clone.get_address(0, None).expect("failed to get address");
+
+    assert_that!(address.address).is_not_equal_to(String::new());
+}
+
+#[test]
+fn returns_rpc_errors() {
+    let cli = monerod::Client::localhost(start_monerod());
+
+    let header = cli.get_block_header_by_height(10);
+
+    assert_that!(header).is_err();
+}
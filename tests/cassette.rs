@@ -0,0 +1,144 @@
+use monero_harness::{
+    cassette::Cassette,
+    mock::{self, monerod::Monerod},
+    rpc::{monerod::Client, ClientConfig},
+    Monero,
+};
+use reqwest::Url;
+use spectral::prelude::*;
+use std::{env, fs, path::PathBuf, process};
+
+const ADDRESS: &str = "cassette_address";
+
+fn cassette_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("{}-{}.json", name, process::id()))
+}
+
+fn config(cassette: &Cassette) -> ClientConfig {
+    ClientConfig {
+        cassette: Some(cassette.clone()),
+        ..ClientConfig::default()
+    }
+}
+
+fn client(port: u16, cassette: &Cassette) -> Client {
+    let url = Url::parse(&format!("http://127.0.0.1:{}/json_rpc", port)).unwrap();
+    Client::with_config(url, config(cassette)).expect("failed to create client")
+}
+
+#[tokio::test]
+async fn replays_recorded_session_offline() {
+    let path = cassette_path("replays_recorded_session_offline");
+
+    let (port, recorded_height, recorded_hash) = {
+        let monerod = Monerod::start().expect("failed to start mock monerod");
+        let cassette = Cassette::record(&path).expect("failed to start recording");
+        let cli = client(monerod.port, &cassette);
+
+        let generated = cli
+            .generate_blocks(5, ADDRESS)
+            .await
+            .expect("failed to generate blocks");
+        let header = cli
+            .get_block_header_by_height(5)
+            .await
+            .expect("failed to get header");
+
+        assert_that!(cassette.interactions()).has_length(2);
+        (monerod.port, generated.height, header.hash)
+    };
+
+    // The mock monerod is gone, responses come from the cassette file.
+    let cassette = Cassette::replay(&path).expect("failed to start replay");
+    let cli = client(port, &cassette);
+
+    let generated = cli
+        .generate_blocks(5, ADDRESS)
+        .await
+        .expect("failed to replay generate blocks");
+    let header = cli
+        .get_block_header_by_height(5)
+        .await
+        .expect("failed to replay get header");
+
+    assert_that!(generated.height).is_equal_to(recorded_height);
+    assert_that!(header.hash).is_equal_to(recorded_hash);
+    assert_that!(cassette.unplayed()).is_empty();
+
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn replays_repeated_requests_in_order() {
+    let path = cassette_path("replays_repeated_requests_in_order");
+
+    let port = {
+        let monerod = Monerod::start().expect("failed to start mock monerod");
+        let cassette = Cassette::record(&path).expect("failed to start recording");
+        let cli = client(monerod.port, &cassette);
+
+        for _ in 0..2 {
+            cli.generate_blocks(1, ADDRESS)
+                .await
+                .expect("failed to generate blocks");
+        }
+        monerod.port
+    };
+
+    let cassette = Cassette::replay(&path).expect("failed to start replay");
+    let cli = client(port, &cassette);
+
+    let first = cli.generate_blocks(1, ADDRESS).await.expect("first replay");
+    let second = cli
+        .generate_blocks(1, ADDRESS)
+        .await
+        .expect("second replay");
+
+    assert_that!(first.height).is_equal_to(1);
+    assert_that!(second.height).is_equal_to(2);
+
+    // Out of recorded interactions.
+    assert!(cli.generate_blocks(1, ADDRESS).await.is_err());
+    // Never recorded.
+    assert!(cli.get_info().await.is_err());
+
+    let _ = fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn records_the_harness_clients() {
+    let path = cassette_path("records_the_harness_clients");
+    let cassette = Cassette::record(&path).expect("failed to start recording");
+
+    let backend = mock::Backend::start(&[]).expect("failed to start mocks");
+    let monero = Monero::from_backend(Box::new(backend))
+        .with_client_config(config(&cassette))
+        .expect("failed to set client config");
+
+    let _ = monero
+        .monerod_rpc_client()
+        .generate_blocks(1, ADDRESS)
+        .await
+        .expect("failed to generate blocks");
+    monero
+        .wallet_rpc_client()
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let methods: Vec<String> = cassette
+        .interactions()
+        .into_iter()
+        .filter_map(|interaction| {
+            interaction.request["method"]
+                .as_str()
+                .map(ToOwned::to_owned)
+        })
+        .collect();
+    assert_that!(methods).is_equal_to(vec![
+        "generateblocks".to_owned(),
+        "create_wallet".to_owned(),
+    ]);
+
+    let _ = fs::remove_file(&path);
+}
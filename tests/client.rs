@@ -1,4 +1,4 @@
-use monero_harness::Monero;
+use monero_harness::{rpc::wallet::KeyType, Monero};
 use spectral::prelude::*;
 use testcontainers::clients::Cli;
 
@@ -34,3 +34,45 @@ async fn init_accounts_for_alice_and_bob() {
     assert_that!(got_balance_alice).is_equal_to(ALICE_FUND_AMOUNT);
     assert_that!(got_balance_bob).is_equal_to(BOB_FUND_AMOUNT);
 }
+
+#[tokio::test]
+async fn init_independent_wallets_for_alice_and_bob() {
+    let cli = init_cli();
+    let monero = Monero::new_with_wallets(&cli, &["alice", "bob"]);
+    monero.init(0, 0).await.expect("failed to initialize");
+
+    monero
+        .init_wallet("alice", ALICE_FUND_AMOUNT)
+        .await
+        .expect("failed to initialize alice's wallet");
+    monero
+        .init_wallet("bob", BOB_FUND_AMOUNT)
+        .await
+        .expect("failed to initialize bob's wallet");
+
+    let alice = monero.wallet("alice").expect("no wallet for alice");
+    let bob = monero.wallet("bob").expect("no wallet for bob");
+
+    let got_balance_alice = alice
+        .get_balance(0)
+        .await
+        .expect("failed to get alice's balance");
+    let got_balance_bob = bob
+        .get_balance(0)
+        .await
+        .expect("failed to get bob's balance");
+
+    assert_that!(got_balance_alice).is_equal_to(ALICE_FUND_AMOUNT);
+    assert_that!(got_balance_bob).is_equal_to(BOB_FUND_AMOUNT);
+
+    let view_key_alice = alice
+        .query_key(KeyType::ViewKey)
+        .await
+        .expect("failed to get alice's view key");
+    let view_key_bob = bob
+        .query_key(KeyType::ViewKey)
+        .await
+        .expect("failed to get bob's view key");
+
+    assert_that!(view_key_alice).is_not_equal_to(view_key_bob);
+}
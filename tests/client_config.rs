@@ -0,0 +1,105 @@
+use monero_harness::{
+    mock::monerod::Monerod,
+    proxy::{Fault, Policy, Proxy},
+    rpc::{monerod::Client, ClientConfig},
+};
+use reqwest::Url;
+use spectral::prelude::*;
+use std::{
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
+fn config(retries: u32) -> ClientConfig {
+    ClientConfig {
+        timeout: Some(Duration::from_millis(500)),
+        retries,
+        retry_backoff: Duration::from_millis(10),
+        ..ClientConfig::default()
+    }
+}
+
+/// Starts a mock monerod behind a proxy running `faults` once each, returns
+/// a client for the proxy with `retries`.
+async fn start(faults: Vec<Fault>, retries: u32) -> (Monerod, Proxy, Client) {
+    let monerod = Monerod::start().expect("failed to start mock monerod");
+    let upstream = Url::parse(&format!("http://127.0.0.1:{}", monerod.port)).unwrap();
+    let proxy = Proxy::start(upstream, Policy::new(faults))
+        .await
+        .expect("failed to start proxy");
+
+    let url = Url::parse(&format!("http://127.0.0.1:{}/json_rpc", proxy.port)).unwrap();
+    let cli = Client::with_config(url, config(retries)).expect("failed to create client");
+
+    (monerod, proxy, cli)
+}
+
+#[tokio::test]
+async fn times_out_on_unresponsive_server() {
+    // Connections are queued by the OS but never accepted.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let url = Url::parse(&format!("http://127.0.0.1:{}/json_rpc", port)).unwrap();
+    let cli = Client::with_config(url, config(0)).expect("failed to create client");
+
+    let start = Instant::now();
+    let err = cli
+        .get_block_header_by_height(0)
+        .await
+        .expect_err("unresponsive server answered");
+
+    let timed_out = err
+        .downcast_ref::<reqwest::Error>()
+        .map(|e| e.is_timeout())
+        .unwrap_or(false);
+    assert!(timed_out, "not a timeout: {}", err);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn retries_idempotent_calls() {
+    let faults = vec![Fault::InternalServerError, Fault::Drop];
+    let (_monerod, proxy, cli) = start(faults, 2).await;
+
+    let header = cli.get_block_header_by_height(0).await;
+
+    assert_that!(header).is_ok();
+    assert_that!(proxy.requests()).is_equal_to(3);
+}
+
+#[tokio::test]
+async fn gives_up_after_retries() {
+    let faults = vec![Fault::InternalServerError; 3];
+    let (_monerod, proxy, cli) = start(faults, 1).await;
+
+    let header = cli.get_block_header_by_height(0).await;
+
+    assert_that!(header).is_err();
+    assert_that!(proxy.requests()).is_equal_to(2);
+}
+
+#[tokio::test]
+async fn never_retries_calls_that_change_state() {
+    let (monerod, proxy, cli) = start(vec![Fault::InternalServerError], 2).await;
+
+    let res = cli.generate_blocks(1, "address").await;
+
+    assert_that!(res).is_err();
+    assert_that!(proxy.requests()).is_equal_to(1);
+    assert_that!(monerod.height()).is_equal_to(1);
+}
+
+#[tokio::test]
+async fn default_config_does_not_retry() {
+    let monerod = Monerod::start().expect("failed to start mock monerod");
+    let upstream = Url::parse(&format!("http://127.0.0.1:{}", monerod.port)).unwrap();
+    let proxy = Proxy::start(upstream, Policy::new(vec![Fault::InternalServerError]))
+        .await
+        .expect("failed to start proxy");
+    let cli = Client::localhost(proxy.port);
+
+    let header = cli.get_block_header_by_height(0).await;
+
+    assert_that!(header).is_err();
+    assert_that!(proxy.requests()).is_equal_to(1);
+}
@@ -0,0 +1,64 @@
+use monero_harness::Monero;
+use spectral::prelude::*;
+use testcontainers::clients::Cli;
+
+const COLD_FUND_AMOUNT: u64 = 1_000_000_000_000;
+
+#[tokio::test]
+async fn sign_offline_and_submit_from_watch_wallet() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    monero.init(0, 0).await.expect("failed to initialize");
+
+    let cold = monero
+        .init_cold_signing(COLD_FUND_AMOUNT)
+        .await
+        .expect("failed to initialize cold signing wallets");
+
+    let address_bob = monero
+        .get_address_bob()
+        .await
+        .expect("failed to get Bob's address")
+        .address;
+
+    let cli = monero.wallet_rpc_client();
+
+    cli.open_wallet(&cold.watch_wallet)
+        .await
+        .expect("failed to open watch wallet");
+
+    let transfer_amount = 100;
+    let transfer = cli
+        .transfer(0, transfer_amount, &address_bob)
+        .await
+        .expect("transfer failed");
+
+    let desc = cli
+        .describe_transfer(&transfer.unsigned_txset)
+        .await
+        .expect("failed to describe transfer");
+
+    assert_that!(desc).has_length(1);
+    assert_that!(desc[0].recipients[0].address).is_equal_to(&address_bob);
+    assert_that!(desc[0].recipients[0].amount).is_equal_to(transfer_amount);
+
+    cli.open_wallet(&cold.signing_wallet)
+        .await
+        .expect("failed to open signing wallet");
+
+    let signed = cli
+        .sign_transfer(&transfer.unsigned_txset)
+        .await
+        .expect("failed to sign transfer");
+
+    cli.open_wallet(&cold.watch_wallet)
+        .await
+        .expect("failed to open watch wallet");
+
+    let tx_hashes = cli
+        .submit_transfer(&signed.signed_txset)
+        .await
+        .expect("failed to submit transfer");
+
+    assert_that!(tx_hashes).is_equal_to(signed.tx_hash_list);
+}
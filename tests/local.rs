@@ -0,0 +1,78 @@
+use monero_harness::{
+    image::{MonerodArgs, WalletArgs},
+    Monero,
+};
+use spectral::prelude::*;
+use std::env;
+
+/// Directory holding the monerod and monero-wallet-rpc binaries.
+const BIN_DIR_VAR: &str = "MONERO_BIN_DIR";
+
+fn bin_dir() -> String {
+    env::var(BIN_DIR_VAR).expect("MONERO_BIN_DIR is not set")
+}
+
+// Needs local monero binaries, run with `MONERO_BIN_DIR=... cargo test --
+// --ignored`.
+#[tokio::test]
+#[ignore]
+async fn init_accounts_with_local_binaries() {
+    let alice_funding = 1_000_000_000_000;
+
+    let monero = Monero::new_local(bin_dir(), &[]).expect("failed to start local binaries");
+    monero.init(alice_funding, 0).await.expect("failed to init");
+
+    let balance = monero
+        .get_balance_alice()
+        .await
+        .expect("failed to get alice's balance");
+
+    assert_that!(balance).is_equal_to(alice_funding);
+}
+
+#[tokio::test]
+#[ignore]
+async fn independent_wallets_with_local_binaries() {
+    let monero = Monero::new_local(bin_dir(), &["alice"]).expect("failed to start local binaries");
+    monero.init_just_miner(70).await.expect("failed to init");
+    monero
+        .sync_wallet()
+        .await
+        .expect("failed to sync the miner wallet");
+    monero
+        .init_wallet("alice", 1_000_000_000_000)
+        .await
+        .expect("failed to init alice's wallet");
+
+    let alice = monero.wallet("alice").expect("no wallet-rpc for alice");
+    let balance = alice.get_balance(0).await.expect("failed to get balance");
+
+    assert_that!(balance).is_equal_to(1_000_000_000_000);
+}
+
+#[tokio::test]
+#[ignore]
+async fn monerod_args_with_local_binaries() {
+    let monerod = MonerodArgs {
+        fixed_difficulty: 10,
+        ..MonerodArgs::default()
+    };
+    let monero = Monero::new_local_with_args(bin_dir(), &[], monerod, WalletArgs::default())
+        .expect("failed to start local binaries");
+    monero.init_just_miner(1).await.expect("failed to init");
+
+    let header = monero
+        .monerod_rpc_client()
+        .get_block_header_by_height(1)
+        .await
+        .expect("failed to get block");
+
+    assert_that!(header.difficulty).is_equal_to(10);
+}
+
+#[test]
+fn fails_without_binaries() {
+    let res = Monero::new_local("/nonexistent", &[]);
+
+    assert!(res.is_err());
+}
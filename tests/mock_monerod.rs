@@ -0,0 +1,111 @@
+use monero_harness::{
+    mock::monerod::{Monerod, BLOCK_REWARD},
+    rpc::{monerod::Client, Credentials},
+};
+use spectral::prelude::*;
+
+const ADDRESS: &str = "mock_address";
+
+#[tokio::test]
+async fn starts_with_genesis_block() {
+    let monerod = Monerod::start().expect("failed to start mock monerod");
+    let cli = monerod.client();
+
+    let header = cli
+        .get_block_header_by_height(0)
+        .await
+        .expect("failed to get genesis header");
+
+    assert_that!(header.height).is_equal_to(0);
+    assert_that!(header.prev_hash).is_equal_to("0".repeat(64));
+    assert_that!(monerod.height()).is_equal_to(1);
+}
+
+#[tokio::test]
+async fn generates_and_pops_blocks() {
+    let monerod = Monerod::start().expect("failed to start mock monerod");
+    let cli = monerod.client();
+
+    let res = cli
+        .generate_blocks(10, ADDRESS)
+        .await
+        .expect("failed to generate blocks");
+    assert_that!(res.height).is_equal_to(10);
+    assert_that!(res.blocks).has_length(10);
+
+    let header = cli
+        .get_block_header_by_height(10)
+        .await
+        .expect("failed to get header");
+    assert_that!(header.hash).is_equal_to(&res.blocks[9]);
+    assert_that!(header.reward).is_equal_to(BLOCK_REWARD);
+
+    let popped = cli.pop_blocks(4).await.expect("failed to pop blocks");
+    assert_that!(popped.height).is_equal_to(7);
+
+    let info = cli.get_info().await.expect("failed to get info");
+    assert_that!(info.height).is_equal_to(7);
+    assert_that!(monerod.reward_addresses()[6].as_str()).is_equal_to(ADDRESS);
+
+    assert!(cli.get_block_header_by_height(10).await.is_err());
+}
+
+#[tokio::test]
+async fn mines_pool_transactions() {
+    let monerod = Monerod::start().expect("failed to start mock monerod");
+    let cli = monerod.client();
+
+    let sent = cli
+        .send_raw_transaction("deadbeef")
+        .await
+        .expect("failed to send transaction");
+    assert_that!(sent.status.as_str()).is_equal_to("OK");
+
+    let pool = monerod.tx_pool();
+    assert_that!(pool).has_length(1);
+    let hash = pool[0].clone();
+
+    let res = cli
+        .get_transactions(vec![hash.clone(), "unknown".to_owned()])
+        .await
+        .expect("failed to get transactions");
+    assert_that!(res.txs).has_length(1);
+    assert!(res.txs[0].in_pool);
+    assert_that!(res.missed_tx).is_equal_to(vec!["unknown".to_owned()]);
+
+    cli.generate_blocks(1, ADDRESS)
+        .await
+        .expect("failed to generate block");
+
+    let res = cli
+        .get_transactions(vec![hash])
+        .await
+        .expect("failed to get transactions");
+    assert!(!res.txs[0].in_pool);
+    assert_that!(res.txs[0].block_height).is_equal_to(1);
+    assert_that!(monerod.tx_pool()).is_empty();
+}
+
+#[tokio::test]
+async fn requires_digest_authentication() {
+    let login = Credentials::new("user", "pass");
+    let monerod =
+        Monerod::start_with_login(Some(login.clone())).expect("failed to start mock monerod");
+
+    let anonymous = Client::localhost(monerod.port);
+    assert!(anonymous.get_info().await.is_err());
+
+    let wrong = Client::localhost(monerod.port).with_credentials(Credentials::new("user", "nope"));
+    assert!(wrong.get_info().await.is_err());
+
+    let cli = Client::localhost(monerod.port).with_credentials(login);
+    // The first request answers the challenge, the rest reuse its nonce.
+    for _ in 0..3 {
+        cli.generate_blocks(1, ADDRESS)
+            .await
+            .expect("failed to generate blocks");
+    }
+    cli.pop_blocks(1).await.expect("failed to pop blocks");
+
+    assert_that!(monerod.height()).is_equal_to(3);
+}
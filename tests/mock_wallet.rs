@@ -0,0 +1,139 @@
+use monero_harness::{
+    mock::{
+        self,
+        wallet::{WalletRpc, TRANSFER_FEE},
+    },
+    Monero,
+};
+use spectral::prelude::*;
+
+const WALLET: &str = "mock_wallet";
+const OTHER_WALLET: &str = "other_mock_wallet";
+
+#[tokio::test]
+async fn creates_wallets_and_accounts() {
+    let rpc = WalletRpc::start().expect("failed to start mock wallet-rpc");
+    let cli = rpc.client();
+
+    cli.create_wallet(WALLET)
+        .await
+        .expect("failed to create wallet");
+    let primary = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+    let account = cli
+        .create_account("savings")
+        .await
+        .expect("failed to create account");
+
+    assert_that!(account.account_index).is_equal_to(1);
+    assert_that!(account.address).is_not_equal_to(&primary.address);
+
+    let accounts = cli.get_accounts("").await.expect("failed to get accounts");
+    assert_that!(accounts.subaddress_accounts).has_length(2);
+    assert_that!(accounts.subaddress_accounts[1].label.as_str()).is_equal_to("savings");
+
+    let height = cli.block_height().await.expect("failed to get height");
+    assert_that!(height.height).is_equal_to(1);
+}
+
+#[tokio::test]
+async fn transfers_between_wallets() {
+    let rpc = WalletRpc::start().expect("failed to start mock wallet-rpc");
+    let cli = rpc.client();
+
+    cli.create_wallet(OTHER_WALLET)
+        .await
+        .expect("failed to create wallet");
+    let to = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+
+    cli.create_wallet(WALLET)
+        .await
+        .expect("failed to create wallet");
+    let fund = 1_000_000_000;
+    let amount = 400_000_000;
+    rpc.fund(WALLET, 0, fund).expect("failed to fund wallet");
+
+    let transfer = cli
+        .transfer(0, amount, &to.address)
+        .await
+        .expect("failed to transfer");
+    assert_that!(transfer.fee).is_equal_to(TRANSFER_FEE);
+
+    let balance = cli.get_balance(0).await.expect("failed to get balance");
+    assert_that!(balance).is_equal_to(fund - amount - TRANSFER_FEE);
+
+    rpc.set_height(11);
+    let check = cli
+        .check_tx_key(&transfer.tx_hash, &transfer.tx_key, &to.address)
+        .await
+        .expect("failed to check tx key");
+    assert_that!(check.received).is_equal_to(amount);
+    assert_that!(check.confirmations).is_equal_to(10);
+
+    cli.open_wallet(OTHER_WALLET)
+        .await
+        .expect("failed to open wallet");
+    let balance = cli.get_balance(0).await.expect("failed to get balance");
+    assert_that!(balance).is_equal_to(amount);
+}
+
+#[tokio::test]
+async fn returns_errors() {
+    let rpc = WalletRpc::start().expect("failed to start mock wallet-rpc");
+    let cli = rpc.client();
+
+    cli.create_wallet(WALLET)
+        .await
+        .expect("failed to create wallet");
+    let address = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+
+    // Unfunded.
+    assert!(cli.transfer(0, 1, &address.address).await.is_err());
+
+    rpc.fail_next("get_balance", "scripted failure");
+    assert!(cli.get_balance(0).await.is_err());
+    assert!(cli.get_balance(0).await.is_ok());
+}
+
+#[tokio::test]
+async fn harness_helpers_run_against_mocks() {
+    let backend = mock::Backend::start(&["alice"]).expect("failed to start mocks");
+    let wallet_rpc = backend.wallet_rpc.clone();
+    let monero = Monero::from_backend(Box::new(backend));
+    assert!(monero.docker().is_none());
+
+    monero.init(0, 0).await.expect("failed to initialize");
+
+    let miner = monero
+        .get_address_primary()
+        .await
+        .expect("failed to get address")
+        .address;
+    let funding = 1_000_000_000;
+    wallet_rpc
+        .fund_address(&miner, 2 * funding)
+        .expect("failed to fund miner");
+
+    monero
+        .init_wallet("alice", funding)
+        .await
+        .expect("failed to initialize wallet");
+
+    let alice = monero.wallet("alice").expect("alice has a wallet-rpc");
+    let balance = alice.get_balance(0).await.expect("failed to get balance");
+    assert_that!(balance).is_equal_to(funding);
+
+    let balance = monero
+        .get_balance_primary()
+        .await
+        .expect("failed to get balance");
+    assert_that!(balance).is_equal_to(funding - TRANSFER_FEE);
+}
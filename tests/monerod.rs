@@ -1,4 +1,8 @@
-use monero_harness::{rpc::monerod::Client, Monero};
+use monero_harness::{
+    image::{MonerodArgs, WalletArgs},
+    rpc::monerod::Client,
+    Monero,
+};
 use spectral::prelude::*;
 use std::time::Duration;
 use testcontainers::clients::Cli;
@@ -45,3 +49,46 @@ async fn miner_is_running_and_producing_blocks() {
 
     assert_that!(header.height).is_equal_to(5);
 }
+
+#[tokio::test]
+async fn monerod_runs_with_given_args() {
+    let tc = init_cli();
+    let monerod = MonerodArgs {
+        fixed_difficulty: 10,
+        ..MonerodArgs::default()
+    };
+    let monero = Monero::new_with_args(&tc, &[], monerod, WalletArgs::default())
+        .expect("failed to start monero");
+
+    monero
+        .init_just_miner(1)
+        .await
+        .expect("Failed to initialize");
+
+    let header = monero
+        .monerod_rpc_client()
+        .get_block_header_by_height(1)
+        .await
+        .expect("failed to get block");
+
+    assert_that!(header.difficulty).is_equal_to(10);
+}
+
+#[tokio::test]
+async fn monerod_and_wallet_rpc_over_tls() {
+    let tc = init_cli();
+    let monero = Monero::new_with_tls(&tc, &[]).expect("failed to start monero");
+
+    monero
+        .init_just_miner(2)
+        .await
+        .expect("Failed to initialize");
+
+    let header = monero
+        .monerod_rpc_client()
+        .get_block_header_by_height(2)
+        .await
+        .expect("failed to get block");
+
+    assert_that!(header.height).is_equal_to(2);
+}
@@ -0,0 +1,57 @@
+use monero_harness::Monero;
+use spectral::prelude::*;
+use testcontainers::clients::Cli;
+
+const MULTISIG_FUND_AMOUNT: u64 = 1_000_000_000_000;
+
+#[tokio::test]
+async fn fund_and_spend_from_two_of_three_multisig() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    monero.init(0, 0).await.expect("failed to initialize");
+
+    let multisig = monero
+        .init_multisig(2, 3, MULTISIG_FUND_AMOUNT)
+        .await
+        .expect("failed to initialize multisig wallet");
+
+    let address_bob = monero
+        .get_address_bob()
+        .await
+        .expect("failed to get Bob's address")
+        .address;
+
+    let cli = monero.wallet_rpc_client();
+
+    cli.open_wallet(&multisig.wallets[0])
+        .await
+        .expect("failed to open first multisig wallet");
+
+    let info = cli.is_multisig().await.expect("failed to query multisig");
+    assert!(info.multisig);
+    assert!(info.ready);
+    assert_that!(info.threshold).is_equal_to(2);
+    assert_that!(info.total).is_equal_to(3);
+
+    let transfer_amount = 100;
+    let transfer = cli
+        .transfer(0, transfer_amount, &address_bob)
+        .await
+        .expect("transfer failed");
+
+    cli.open_wallet(&multisig.wallets[1])
+        .await
+        .expect("failed to open second multisig wallet");
+
+    let signed = cli
+        .sign_multisig(&transfer.multisig_txset)
+        .await
+        .expect("failed to sign multisig transaction");
+
+    let tx_hashes = cli
+        .submit_multisig(&signed.tx_data_hex)
+        .await
+        .expect("failed to submit multisig transaction");
+
+    assert_that!(tx_hashes).has_length(1);
+}
@@ -0,0 +1,184 @@
+use monero_harness::{network::Network, rpc::wallet::TransferType};
+use spectral::prelude::*;
+use testcontainers::clients::Cli;
+
+#[test]
+fn network_needs_at_least_two_nodes() {
+    let tc = Cli::default();
+
+    assert_that!(Network::new(&tc, 1)).is_err();
+}
+
+#[tokio::test]
+async fn nodes_connect_and_propagate_blocks() {
+    let tc = Cli::default();
+    let network = Network::new(&tc, 2).expect("failed to start network");
+
+    network
+        .wait_for_peers()
+        .await
+        .expect("failed to wait for peers");
+
+    let status = network.status().await.expect("failed to get status");
+    for node in &status {
+        assert_that!(node.connections).has_length(1);
+    }
+
+    let wallet = network.wallet_rpc_client(0);
+    let _ = wallet
+        .create_wallet("miner_wallet")
+        .await
+        .expect("failed to create wallet");
+    let miner = wallet
+        .get_address(0, None)
+        .await
+        .expect("failed to get address")
+        .address;
+
+    let res = network
+        .monerod_rpc_client(0)
+        .generate_blocks(10, &miner)
+        .await
+        .expect("failed to generate blocks");
+
+    network
+        .wait_for_height(res.height)
+        .await
+        .expect("failed to wait for block propagation");
+
+    // Without a partition there is no reorg, the blocks survive on both nodes.
+    for node in 0..2 {
+        let orphaned = network
+            .orphaned(node, &res)
+            .await
+            .expect("failed to check for orphaned blocks");
+        assert_that!(orphaned).is_empty();
+    }
+}
+
+#[tokio::test]
+async fn partitioned_nodes_reorg_onto_longest_chain() {
+    let tc = Cli::default();
+    let network = Network::new(&tc, 2).expect("failed to start network");
+
+    network
+        .wait_for_peers()
+        .await
+        .expect("failed to wait for peers");
+
+    let wallet = network.wallet_rpc_client(0);
+    let _ = wallet
+        .create_wallet("miner_wallet")
+        .await
+        .expect("failed to create wallet");
+    let miner = wallet
+        .get_address(0, None)
+        .await
+        .expect("failed to get address")
+        .address;
+
+    let reorg = network
+        .reorg(0, 3, 1, 5, &miner)
+        .await
+        .expect("failed to reorg");
+
+    assert_that!(reorg.winner).is_equal_to(1);
+    assert_that!(reorg.depth).is_equal_to(3);
+    assert_that!(reorg.orphaned).has_length(3);
+
+    let status = network.status().await.expect("failed to get status");
+    for node in &status {
+        assert_that!(node.height).is_equal_to(reorg.height);
+    }
+}
+
+#[tokio::test]
+async fn double_spend_is_resolved_by_the_longest_chain() {
+    let tc = Cli::default();
+    let network = Network::new(&tc, 2).expect("failed to start network");
+
+    network
+        .wait_for_peers()
+        .await
+        .expect("failed to wait for peers");
+
+    let wallet = network.wallet_rpc_client(0);
+    let _ = wallet
+        .create_wallet("miner_wallet")
+        .await
+        .expect("failed to create wallet");
+    let miner = wallet
+        .get_address(0, None)
+        .await
+        .expect("failed to get address")
+        .address;
+
+    // Coinbase outputs need 60 confirmations before they can be spent.
+    let res = network
+        .monerod_rpc_client(0)
+        .generate_blocks(70, &miner)
+        .await
+        .expect("failed to generate blocks");
+    network
+        .wait_for_height(res.height)
+        .await
+        .expect("failed to wait for block propagation");
+    wallet.refresh(None).await.expect("failed to refresh");
+
+    let outputs = wallet
+        .incoming_transfers(TransferType::Available, 0)
+        .await
+        .expect("failed to get outputs");
+    let key_image = &outputs[0].key_image;
+
+    let address_a = wallet
+        .create_address(0, "a", 1)
+        .await
+        .expect("failed to create address")
+        .address;
+    let address_b = wallet
+        .create_address(0, "b", 1)
+        .await
+        .expect("failed to create address")
+        .address;
+
+    let double_spend = network
+        .double_spend(0, 1, key_image, &address_a, &address_b)
+        .await
+        .expect("failed to double spend");
+
+    // Node 1 mines the longer chain so the second transaction wins.
+    network
+        .monerod_rpc_client(0)
+        .generate_blocks(1, &miner)
+        .await
+        .expect("failed to generate blocks");
+    let res = network
+        .monerod_rpc_client(1)
+        .generate_blocks(2, &miner)
+        .await
+        .expect("failed to generate blocks");
+
+    network.reconnect(0, 1).await.expect("failed to reconnect");
+    network
+        .wait_for_height(res.height)
+        .await
+        .expect("failed to wait for reorg");
+
+    let got = network
+        .monerod_rpc_client(0)
+        .get_transactions(vec![
+            double_spend.first.tx_hash.clone(),
+            double_spend.second.tx_hash.clone(),
+        ])
+        .await
+        .expect("failed to get transactions");
+
+    let mined: Vec<&str> = got
+        .txs
+        .iter()
+        .filter(|tx| !tx.in_pool)
+        .map(|tx| tx.tx_hash.as_str())
+        .collect();
+    assert_that!(mined).is_equal_to(vec![double_spend.second.tx_hash.as_str()]);
+}
@@ -0,0 +1,163 @@
+use hyper::{
+    header::{AUTHORIZATION, WWW_AUTHENTICATE},
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use monero_harness::{
+    proxy::{Fault, Policy, Proxy},
+    rpc::monerod::Client,
+};
+use reqwest::Url;
+use spectral::prelude::*;
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tokio::time;
+
+const STUB_HEIGHT: u32 = 42;
+
+/// Starts a stub monerod that answers every request with a generateblocks
+/// response, returns its URL.
+fn start_stub_monerod() -> Url {
+    let body = format!(
+        "{{\"id\":\"1\",\"jsonrpc\":\"2.0\",\"result\":{{\"blocks\":[],\"height\":{},\"status\":\"OK\"}}}}",
+        STUB_HEIGHT
+    );
+
+    let make_service = make_service_fn(move |_| {
+        let body = body.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |_| {
+                let body = body.clone();
+                async move { Ok::<_, Infallible>(Response::new(Body::from(body))) }
+            }))
+        }
+    });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let server = Server::bind(&addr).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+
+    Url::parse(&url).expect("url is well formed")
+}
+
+/// Starts a stub server that answers every request with 401, echoing the
+/// request's method, path and query in the body and its authorization
+/// header in the challenge, returns its URL.
+fn start_echo_server() -> Url {
+    let make_service = make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|request: Request<Body>| async move {
+            let authorization = request
+                .headers()
+                .get(AUTHORIZATION)
+                .cloned()
+                .unwrap_or_else(|| "none".parse().expect("valid header value"));
+            let body = format!("{} {}", request.method(), request.uri());
+
+            Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .header(WWW_AUTHENTICATE, authorization)
+                .body(Body::from(body))
+        }))
+    });
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let server = Server::bind(&addr).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+
+    Url::parse(&url).expect("url is well formed")
+}
+
+async fn start_proxy(policy: Policy) -> (Proxy, Client) {
+    let proxy = Proxy::start(start_stub_monerod(), policy)
+        .await
+        .expect("failed to start proxy");
+    let cli = Client::localhost(proxy.port);
+
+    (proxy, cli)
+}
+
+#[tokio::test]
+async fn forwards_requests_without_faults() {
+    let (proxy, cli) = start_proxy(Policy::default()).await;
+
+    let res = cli
+        .generate_blocks(1, "address")
+        .await
+        .expect("failed to generate blocks");
+
+    assert_that!(res.height).is_equal_to(STUB_HEIGHT);
+    assert_that!(proxy.requests()).is_equal_to(1);
+}
+
+#[tokio::test]
+async fn runs_through_scripted_faults() {
+    let policy = Policy::new(vec![
+        Fault::InternalServerError,
+        Fault::MalformedJson,
+        Fault::Drop,
+    ]);
+    let (proxy, cli) = start_proxy(policy).await;
+
+    for _ in 0..3 {
+        let res = cli.generate_blocks(1, "address").await;
+        assert!(res.is_err());
+    }
+
+    let res = cli
+        .generate_blocks(1, "address")
+        .await
+        .expect("failed to generate blocks after the script ended");
+
+    assert_that!(res.height).is_equal_to(STUB_HEIGHT);
+    assert_that!(proxy.requests()).is_equal_to(4);
+}
+
+#[tokio::test]
+async fn injects_latency() {
+    let delay = Duration::from_millis(500);
+    let (_proxy, cli) = start_proxy(Policy::always(Fault::Latency(delay))).await;
+
+    let start = Instant::now();
+    let _ = cli
+        .generate_blocks(1, "address")
+        .await
+        .expect("failed to generate blocks");
+
+    assert!(start.elapsed() >= delay);
+}
+
+#[tokio::test]
+async fn stalls_until_the_client_gives_up() {
+    let (proxy, cli) = start_proxy(Policy::default()).await;
+    proxy.set_policy(Policy::always(Fault::Stall));
+
+    let res = time::timeout(Duration::from_secs(1), cli.generate_blocks(1, "address")).await;
+
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn forwards_method_query_and_headers() {
+    let proxy = Proxy::start(start_echo_server(), Policy::default())
+        .await
+        .expect("failed to start proxy");
+
+    let url = format!("http://127.0.0.1:{}/get_info?verbose=1", proxy.port);
+    let res = reqwest::Client::new()
+        .get(&url)
+        .header(AUTHORIZATION, "Digest username=\"user\"")
+        .send()
+        .await
+        .expect("failed to send request");
+
+    assert_that!(res.status()).is_equal_to(StatusCode::UNAUTHORIZED);
+    let challenge = res.headers().get(WWW_AUTHENTICATE).cloned();
+    assert_that!(challenge).is_equal_to(Some("Digest username=\"user\"".parse().unwrap()));
+    let body = res.text().await.expect("failed to read body");
+    assert_that!(body.as_str()).is_equal_to("GET /get_info?verbose=1");
+}
@@ -0,0 +1,77 @@
+use monero_harness::{
+    mock::{monerod::Monerod, wallet::WalletRpc},
+    rpc::{monerod::Client, Certificate, Tls},
+};
+use spectral::prelude::*;
+
+fn start() -> (Monerod, Certificate) {
+    let certificate = Certificate::generate().expect("failed to generate certificate");
+    let monerod =
+        Monerod::start_with_tls(certificate.clone()).expect("failed to start mock monerod");
+
+    (monerod, certificate)
+}
+
+#[tokio::test]
+async fn trusts_endpoint_signed_by_ca_certificate() {
+    let (monerod, _) = start();
+
+    let header = monerod.client().get_block_header_by_height(0).await;
+
+    assert_that!(header).is_ok();
+}
+
+#[tokio::test]
+async fn rejects_endpoint_signed_by_unknown_ca() {
+    let (monerod, _) = start();
+    let other = Certificate::generate().expect("failed to generate certificate");
+    let cli = Client::localhost_with_tls(monerod.port, Tls::CaCertificate(other.certificate))
+        .expect("failed to create client");
+
+    let header = cli.get_block_header_by_height(0).await;
+
+    assert_that!(header).is_err();
+}
+
+#[tokio::test]
+async fn accepts_pinned_fingerprint_only() {
+    let (monerod, certificate) = start();
+    let other = Certificate::generate().expect("failed to generate certificate");
+
+    let pinned = certificate
+        .fingerprint()
+        .expect("failed to get fingerprint");
+    let cli = Client::localhost_with_tls(monerod.port, Tls::Fingerprint(pinned))
+        .expect("failed to create client");
+    assert_that!(cli.get_block_header_by_height(0).await).is_ok();
+
+    let wrong = other.fingerprint().expect("failed to get fingerprint");
+    let cli = Client::localhost_with_tls(monerod.port, Tls::Fingerprint(wrong))
+        .expect("failed to create client");
+    assert_that!(cli.get_block_header_by_height(0).await).is_err();
+}
+
+#[tokio::test]
+async fn accepts_any_cert_when_asked_to() {
+    let (monerod, _) = start();
+    let cli = Client::localhost_with_tls(monerod.port, Tls::AcceptAnyCert)
+        .expect("failed to create client");
+
+    let header = cli.get_block_header_by_height(0).await;
+
+    assert_that!(header).is_ok();
+}
+
+#[tokio::test]
+async fn wallet_rpc_over_tls() {
+    let certificate = Certificate::generate().expect("failed to generate certificate");
+    let wallet_rpc = WalletRpc::start_with_tls(certificate).expect("failed to start mock wallet");
+    let cli = wallet_rpc.client();
+
+    cli.create_wallet("alice")
+        .await
+        .expect("failed to create wallet");
+    let balance = cli.get_balance(0).await.expect("failed to get balance");
+
+    assert_that!(balance).is_equal_to(0);
+}
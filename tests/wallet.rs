@@ -1,4 +1,10 @@
-use monero_harness::{rpc::wallet::Client, Monero};
+use monero_harness::{
+    rpc::{
+        wallet::{Client, KeyType, PaymentUri, Priority, SubAddressIndex, TransferType},
+        Credentials,
+    },
+    Monero,
+};
 use spectral::prelude::*;
 use testcontainers::clients::Cli;
 
@@ -21,6 +27,25 @@ async fn wallet_and_accounts() {
     assert_that!(got).is_equal_to(want);
 }
 
+#[tokio::test]
+async fn wallet_rpc_with_login() {
+    let tc = Cli::default();
+    let login = Credentials::new("harness", "secret");
+    let monero = Monero::new_with_login(&tc, &[], login);
+
+    let anonymous = Client::localhost(monero.wallet_rpc_port);
+    assert!(anonymous.create_wallet("wallet").await.is_err());
+
+    let cli = monero.wallet_rpc_client();
+    cli.create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let got = cli.get_balance(0).await.expect("failed to get balance");
+
+    assert_that!(got).is_equal_to(0);
+}
+
 #[tokio::test]
 async fn create_account_and_retrieve_it() {
     let tc = Cli::default();
@@ -84,3 +109,389 @@ async fn transfer_and_check_tx_key() {
 
     assert_that!(res.received).is_equal_to(transfer_amount);
 }
+
+#[tokio::test]
+async fn create_subaddresses_and_retrieve_them() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let label = "deposit";
+    let created = cli
+        .create_address(0, label, 1)
+        .await
+        .expect("failed to create subaddress");
+
+    let index = cli
+        .get_address_index(&created.address)
+        .await
+        .expect("failed to get subaddress index");
+    assert_that!(index.major).is_equal_to(0);
+    assert_that!(index.minor).is_equal_to(created.address_index);
+
+    let relabel = "deposit (paid)";
+    cli.label_address(index, relabel)
+        .await
+        .expect("failed to label subaddress");
+
+    let got = cli
+        .get_address(0, Some(vec![created.address_index]))
+        .await
+        .expect("failed to get subaddresses");
+
+    assert_that!(got.addresses).has_length(1);
+    assert_that!(got.addresses[0].address).is_equal_to(&created.address);
+    assert_that!(got.addresses[0].label.as_str()).is_equal_to(relabel);
+    assert!(!got.addresses[0].used);
+}
+
+#[tokio::test]
+async fn tag_accounts_and_filter_by_tag() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let exchange = cli
+        .create_account("exchange")
+        .await
+        .expect("failed to create account");
+    let _ = cli
+        .create_account("savings")
+        .await
+        .expect("failed to create account");
+
+    let tag = "hot";
+    cli.tag_accounts(tag, vec![exchange.account_index])
+        .await
+        .expect("failed to tag accounts");
+    cli.set_account_tag_description(tag, "hot wallets")
+        .await
+        .expect("failed to describe tag");
+
+    let tagged = cli.get_accounts(tag).await.expect("failed to get accounts");
+    assert_that!(tagged.subaddress_accounts).has_length(1);
+    assert_that!(tagged.subaddress_accounts[0].account_index).is_equal_to(exchange.account_index);
+    assert_that!(tagged.subaddress_accounts[0].tag.as_str()).is_equal_to(tag);
+
+    cli.untag_accounts(vec![exchange.account_index])
+        .await
+        .expect("failed to untag accounts");
+
+    let all = cli.get_accounts("").await.expect("failed to get accounts");
+    assert_that!(all.subaddress_accounts).has_length(3);
+    assert!(all.subaddress_accounts.iter().all(|a| a.tag.is_empty()));
+}
+
+#[tokio::test]
+async fn make_and_split_integrated_address() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let address = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+
+    let integrated = cli
+        .make_integrated_address("", "")
+        .await
+        .expect("failed to make integrated address");
+
+    let valid = cli
+        .validate_address(&integrated.integrated_address, false)
+        .await
+        .expect("failed to validate address");
+    assert!(valid.valid);
+    assert!(valid.integrated);
+
+    let split = cli
+        .split_integrated_address(&integrated.integrated_address)
+        .await
+        .expect("failed to split integrated address");
+
+    assert_that!(split.standard_address).is_equal_to(address.address);
+    assert_that!(split.payment_id).is_equal_to(integrated.payment_id);
+}
+
+#[tokio::test]
+async fn payment_uri_round_trip() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let address = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+
+    let want = PaymentUri {
+        address: address.address,
+        amount: 1_500_000_000_000,
+        recipient_name: "Merchant".to_owned(),
+        tx_description: "Order 42".to_owned(),
+        ..PaymentUri::default()
+    };
+
+    let uri = cli.make_uri(&want).await.expect("failed to make uri");
+    assert!(uri.starts_with("monero:"));
+
+    let got = cli.parse_uri(&uri).await.expect("failed to parse uri");
+
+    assert_that!(got).is_equal_to(want);
+}
+
+#[tokio::test]
+async fn refresh_and_get_version() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let refreshed = cli.refresh(None).await.expect("failed to refresh");
+    assert!(!refreshed.received_money);
+
+    let version = cli.get_version().await.expect("failed to get version");
+    assert_that!(version.version).is_greater_than(0);
+}
+
+#[tokio::test]
+async fn sign_and_verify_challenge() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let address = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+    let index = SubAddressIndex { major: 0, minor: 0 };
+    let challenge = "login challenge";
+
+    let signature = cli.sign(challenge, index).await.expect("failed to sign");
+
+    let res = cli
+        .verify(challenge, &address.address, &signature)
+        .await
+        .expect("failed to verify");
+    assert!(res.good);
+
+    let res = cli
+        .verify("other challenge", &address.address, &signature)
+        .await
+        .expect("failed to verify");
+    assert!(!res.good);
+}
+
+#[tokio::test]
+async fn query_keys_and_attributes() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    let mnemonic = cli
+        .query_key(KeyType::Mnemonic)
+        .await
+        .expect("failed to query mnemonic");
+    assert_that!(mnemonic.split_whitespace().count()).is_equal_to(25);
+
+    let view_key = cli
+        .query_key(KeyType::ViewKey)
+        .await
+        .expect("failed to query view key");
+    assert_that!(view_key.len()).is_equal_to(64);
+
+    cli.set_attribute("backup", "done")
+        .await
+        .expect("failed to set attribute");
+    let got = cli
+        .get_attribute("backup")
+        .await
+        .expect("failed to get attribute");
+    assert_that!(got.as_str()).is_equal_to("done");
+}
+
+#[tokio::test]
+async fn set_and_get_tx_notes() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    // Notes are stored per tx id, the transactions need not be in the wallet.
+    let noted = "1".repeat(64);
+    let other = "2".repeat(64);
+
+    cli.set_tx_notes(vec![noted.clone()], vec!["deposit #42".to_owned()])
+        .await
+        .expect("failed to set tx notes");
+
+    let notes = cli
+        .get_tx_notes(vec![noted, other])
+        .await
+        .expect("failed to get tx notes");
+
+    assert_that!(notes).is_equal_to(vec!["deposit #42".to_owned(), String::new()]);
+}
+
+#[tokio::test]
+async fn add_edit_and_delete_address_book_entry() {
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let cli = Client::localhost(monero.wallet_rpc_port);
+
+    let _ = cli
+        .create_wallet("wallet")
+        .await
+        .expect("failed to create wallet");
+
+    // Any valid address will do, use our own.
+    let address = cli
+        .get_address(0, None)
+        .await
+        .expect("failed to get address");
+
+    let index = cli
+        .add_address_book(&address.address, "Alice")
+        .await
+        .expect("failed to add address book entry");
+
+    cli.edit_address_book(index, None, Some("Alice (work)"))
+        .await
+        .expect("failed to edit address book entry");
+
+    let entries = cli
+        .get_address_book(vec![index])
+        .await
+        .expect("failed to get address book");
+    assert_that!(entries).has_length(1);
+    assert_that!(entries[0].address).is_equal_to(&address.address);
+    assert_that!(entries[0].description.as_str()).is_equal_to("Alice (work)");
+
+    cli.delete_address_book(index)
+        .await
+        .expect("failed to delete address book entry");
+
+    let entries = cli
+        .get_address_book(vec![])
+        .await
+        .expect("failed to get address book");
+    assert_that!(entries).is_empty();
+}
+
+#[tokio::test]
+async fn freeze_thaw_and_transfer_specific_output() {
+    let fund_alice = 1_000_000_000_000;
+    let fund_bob = 0;
+
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let _ = monero.init(fund_alice, fund_bob).await;
+
+    let address_bob = monero
+        .get_address_bob()
+        .await
+        .expect("failed to get Bob's address")
+        .address;
+
+    let cli = monero.wallet_rpc_client();
+
+    let outputs = monero
+        .get_outputs(1) // Alice's account.
+        .await
+        .expect("failed to get Alice's outputs");
+    assert_that!(outputs).has_length(1);
+    let key_image = outputs[0].key_image.clone();
+
+    cli.freeze(&key_image).await.expect("failed to freeze");
+    assert!(cli
+        .frozen(&key_image)
+        .await
+        .expect("failed to check frozen"));
+
+    cli.thaw(&key_image).await.expect("failed to thaw");
+    assert!(!cli
+        .frozen(&key_image)
+        .await
+        .expect("failed to check frozen"));
+
+    let transfers = monero
+        .transfer_outputs(1, &[key_image.clone()], &address_bob)
+        .await
+        .expect("failed to transfer output");
+    assert_that!(transfers).has_length(1);
+
+    let spent = cli
+        .incoming_transfers(TransferType::Unavailable, 1)
+        .await
+        .expect("failed to get spent outputs");
+    assert!(spent.iter().any(|output| output.key_image == key_image));
+}
+
+#[tokio::test]
+async fn send_max_from_account_matches_wallet_fee() {
+    let fund_alice = 1_000_000_000_000;
+    let fund_bob = 0;
+
+    let tc = Cli::default();
+    let monero = Monero::new(&tc);
+    let _ = monero.init(fund_alice, fund_bob).await;
+
+    let address_bob = monero
+        .get_address_bob()
+        .await
+        .expect("failed to get Bob's address")
+        .address;
+
+    let send_max = monero
+        .get_send_max(1, Priority::Default) // Alice's account.
+        .await
+        .expect("failed to compute send max");
+    assert_that!(send_max.amount + send_max.fee).is_equal_to(fund_alice);
+
+    let cli = monero.wallet_rpc_client();
+    let transfer = cli
+        .transfer_with_priority(1, send_max.amount, &address_bob, Priority::Default)
+        .await
+        .expect("failed to send max");
+
+    assert_that!(transfer.fee).is_equal_to(send_max.fee);
+}